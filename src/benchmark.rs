@@ -0,0 +1,32 @@
+// runs the real update_watchers against any GameMemory, for the tick_benchmark host tool.
+// the watchers and the memory layout stay private, the tool only picks the game and the timer mode
+use crate::{
+    memory::{update_watchers, Memory, ReadSettings},
+    TimerMode, Watchers,
+};
+
+pub use crate::game::GameMemory;
+
+pub struct WatcherBench {
+    memory: Memory,
+    watchers: Watchers,
+    settings: ReadSettings,
+}
+
+impl WatcherBench {
+    pub fn new(game: &impl GameMemory, timer_mode: TimerMode) -> Self {
+        Self {
+            memory: Memory::init(game),
+            watchers: Watchers::default(),
+            settings: ReadSettings {
+                timer_mode,
+                remove_loads_il: true,
+                profile_tick_cost: false,
+            },
+        }
+    }
+
+    pub fn tick(&mut self, game: &impl GameMemory) {
+        update_watchers(game, &mut self.memory, &mut self.watchers, self.settings);
+    }
+}
@@ -0,0 +1,86 @@
+use asr::settings::{Map, Value};
+
+use crate::{stages::GameStage, text::Text};
+
+// what a best is kept for, the stage bests are stage clock times and the level and segment bests
+// are the game time of the timer that ran them
+#[derive(Clone, Copy, PartialEq)]
+pub enum BestKind {
+    Stage,
+    Level,
+    // the checkpoints the segment starts and ends on, -1 is the level start and no end is the goal
+    Segment { start: i32, end: Option<i32> },
+    // the time trial timer, with or without the bonus clocks taken off
+    TimeTrial { discount_bonus: bool },
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct BestKey {
+    pub kind: BestKind,
+    pub stage: GameStage,
+}
+
+// best times kept between sessions in the settings map, next to the settings themselves.
+// the best of the current key is cached so the map is only loaded when the stage or mode changes
+#[derive(Default)]
+pub struct Bests {
+    cached: Option<(BestKey, Option<f64>)>,
+}
+
+impl Bests {
+    pub fn best(&mut self, key: BestKey) -> Option<f64> {
+        match self.cached {
+            Some((cached_key, best)) if cached_key == key => best,
+            _ => {
+                let best = Map::load()
+                    .get(map_key(key).as_str())
+                    .and_then(|value| value.get_f64());
+                self.cached = Some((key, best));
+                best
+            }
+        }
+    }
+
+    // keeps the time if it's a new best and gives the best it replaced
+    pub fn submit(&mut self, key: BestKey, time: f64) -> Option<Option<f64>> {
+        let previous = self.best(key);
+        if previous.is_some_and(|best| best <= time) || time <= 0. {
+            return None;
+        }
+
+        // the settings map holds the settings too, storing a stale copy would undo a settings change
+        // made since it was loaded
+        loop {
+            let old = Map::load();
+            let map = old.clone();
+            map.insert(map_key(key).as_str(), &Value::from(time));
+            if map.store_if_unchanged(&old) {
+                break;
+            }
+        }
+        self.cached = Some((key, Some(time)));
+        Some(previous)
+    }
+}
+
+// keyed by the scene id, the stage names are only for showing
+fn map_key(key: BestKey) -> Text<64> {
+    let scene = key.stage as u32;
+    match key.kind {
+        BestKind::Stage => Text::format(format_args!("best_stage_{scene}")),
+        BestKind::Level => Text::format(format_args!("best_level_{scene}")),
+        BestKind::Segment {
+            start,
+            end: Some(end),
+        } => Text::format(format_args!("best_segment_{scene}_{start}_{end}")),
+        BestKind::Segment { start, end: None } => {
+            Text::format(format_args!("best_segment_{scene}_{start}_goal"))
+        }
+        BestKind::TimeTrial {
+            discount_bonus: true,
+        } => Text::format(format_args!("best_time_trial_{scene}")),
+        BestKind::TimeTrial {
+            discount_bonus: false,
+        } => Text::format(format_args!("best_time_trial_raw_{scene}")),
+    }
+}
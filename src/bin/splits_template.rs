@@ -0,0 +1,161 @@
+// Writes LiveSplit .lss split files that line up with the splits the auto splitter fires.
+//
+// Run it on the host, the .cargo config builds for wasm by default:
+// cargo run --features host-tools --bin splits_template --target <host triple> -- [output folder] [<scene id> <checkpoints>]...
+
+use std::{env, error::Error, fs, path::Path};
+
+#[allow(dead_code)]
+#[path = "../stages.rs"]
+mod stages;
+
+use stages::{Era, GameStage, StageKind, STAGE_SAVE_ORDER};
+
+const GAME_NAME: &str = "PAC-MAN WORLD 2 Re-PAC";
+
+struct Template {
+    category: String,
+    segments: Vec<String>,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = env::args().skip(1);
+    let output_dir = args.next().unwrap_or_else(|| "splits".to_string());
+    let output_dir = Path::new(&output_dir);
+    fs::create_dir_all(output_dir)?;
+
+    let mut templates = vec![
+        full_game("Full Game".to_string(), Era::Present),
+        full_game("Full Game (Past)".to_string(), Era::Past),
+        full_game("Full Game (Sonic)".to_string(), Era::Sonic),
+        time_trial_marathon(),
+    ];
+    // the checkpoint counts aren't in the tree, every IL template is given its scene id and count
+    while let Some(scene_id) = args.next() {
+        let checkpoints = args
+            .next()
+            .ok_or_else(|| format!("no checkpoint count given for {scene_id}"))?;
+        templates.push(checkpoint_il(&scene_id, &checkpoints)?);
+    }
+
+    for template in &templates {
+        let path = output_dir.join(format!("{}.lss", file_name(&template.category)));
+        fs::write(&path, to_lss(template))?;
+        println!(
+            "{} ({} splits) -> {}",
+            template.category,
+            template.segments.len(),
+            path.display()
+        );
+    }
+
+    Ok(())
+}
+
+// stages that are part of the main route, mazes and bonus stages are optional side content
+fn route_stages(era: Era) -> impl Iterator<Item = GameStage> {
+    STAGE_SAVE_ORDER
+        .iter()
+        .copied()
+        .filter(move |stage| stage.era() == era)
+        .filter(|stage| matches!(stage.kind(), StageKind::Normal | StageKind::Boss))
+}
+
+// the same splits `split_full_game` fires with the default settings: leaving pac-village, every
+// level exit and the final bosses, which split on their defeat instead of their exit
+fn full_game(category: String, era: Era) -> Template {
+    let mut segments = Vec::new();
+    if era == Era::Present {
+        segments.push(GameStage::PacVillage.to_string().to_owned());
+    }
+    segments.extend(route_stages(era).map(|stage| match stage.final_boss() {
+        Some(boss) => boss.to_owned(),
+        None => stage.to_string().to_owned(),
+    }));
+
+    Template { category, segments }
+}
+
+// the marathon splits once per finished time trial
+fn time_trial_marathon() -> Template {
+    Template {
+        category: "Time Trial Marathon".to_string(),
+        segments: route_stages(Era::Present)
+            .map(|stage| stage.to_string().to_owned())
+            .collect(),
+    }
+}
+
+// one split per checkpoint plus the goal
+fn checkpoint_il(scene_id: &str, checkpoints: &str) -> Result<Template, Box<dyn Error>> {
+    let stage = STAGE_SAVE_ORDER
+        .iter()
+        .find(|stage| (**stage as u32).to_string() == scene_id)
+        .ok_or_else(|| format!("unknown stage scene id {scene_id}"))?;
+    let checkpoints: u32 = checkpoints
+        .parse()
+        .map_err(|_| format!("bad checkpoint count for {scene_id}: {checkpoints}"))?;
+
+    let mut segments: Vec<String> = (1..=checkpoints)
+        .map(|checkpoint| format!("Checkpoint {checkpoint}"))
+        .collect();
+    segments.push("Goal".to_string());
+
+    Ok(Template {
+        category: format!("IL - {}", stage.to_string()),
+        segments,
+    })
+}
+
+fn file_name(category: &str) -> String {
+    category
+        .chars()
+        .map(|c| match c {
+            c if c.is_alphanumeric() || c == '-' => c,
+            _ => '_',
+        })
+        .collect::<String>()
+        .trim_matches('_')
+        .to_string()
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn to_lss(template: &Template) -> String {
+    let mut lss = String::new();
+    lss.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    lss.push_str("<Run version=\"1.7.0\">\n");
+    lss.push_str("  <GameIcon />\n");
+    lss.push_str(&format!(
+        "  <GameName>{}</GameName>\n",
+        escape_xml(GAME_NAME)
+    ));
+    lss.push_str(&format!(
+        "  <CategoryName>{}</CategoryName>\n",
+        escape_xml(&template.category)
+    ));
+    lss.push_str("  <Offset>00:00:00</Offset>\n");
+    lss.push_str("  <AttemptCount>0</AttemptCount>\n");
+    lss.push_str("  <AttemptHistory />\n");
+    lss.push_str("  <Segments>\n");
+    for segment in &template.segments {
+        lss.push_str("    <Segment>\n");
+        lss.push_str(&format!("      <Name>{}</Name>\n", escape_xml(segment)));
+        lss.push_str("      <Icon />\n");
+        lss.push_str("      <SplitTimes>\n");
+        lss.push_str("        <SplitTime name=\"Personal Best\" />\n");
+        lss.push_str("      </SplitTimes>\n");
+        lss.push_str("      <BestSegmentTime />\n");
+        lss.push_str("      <SegmentHistory />\n");
+        lss.push_str("    </Segment>\n");
+    }
+    lss.push_str("  </Segments>\n");
+    lss.push_str("  <AutoSplitterSettings />\n");
+    lss.push_str("</Run>\n");
+    lss
+}
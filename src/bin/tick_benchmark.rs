@@ -0,0 +1,204 @@
+// Runs the real update_watchers against a fake game for every timer mode and reports what a tick
+// costs, so a change that adds reads or makes them slower shows up before it reaches a run.
+//
+// cargo run --release --features host-tools --bin tick_benchmark --target <host triple> -- [budget µs] [read cost ns]
+// Exits with an error when the average tick of any mode goes over the budget.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    env, mem,
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+
+use asr::game_engine::unity::il2cpp::UnityPointer;
+use bytemuck::CheckedBitPattern;
+use livesplit_pacman_w2_re_pac::{
+    benchmark::{GameMemory, WatcherBench},
+    TimerMode,
+};
+
+#[allow(dead_code)]
+#[path = "../stages.rs"]
+mod stages;
+
+use stages::GameStage;
+
+const TICKS: u32 = 2000;
+const DEFAULT_BUDGET_MICROS: f64 = 250.;
+// about what a single read of another process costs on a desktop
+const DEFAULT_READ_COST_NANOS: u64 = 1000;
+
+// every manager static leads to this instance, the fields are told apart by their offsets
+const INSTANCE: u64 = 0x1_0000_0000;
+const STATIC_FIELD: u64 = 0x1000;
+// past the object header and the array fields the splitter reads at fixed offsets
+const FIRST_FIELD_OFFSET: u32 = 0x100;
+
+const MODES: [(TimerMode, &str); 6] = [
+    (TimerMode::FullGame, "Full Game"),
+    (TimerMode::ILSeries, "Individual Level Series"),
+    (TimerMode::IL, "Individual Level"),
+    (TimerMode::Segment, "Checkpoint Segment"),
+    (TimerMode::TimeTrial, "Time Trial"),
+    (TimerMode::TimeTrialMarathon, "Time Trial Marathon"),
+];
+
+// stands in for the game, unwritten memory reads as 0 and every read costs the same
+struct FakeGame {
+    read_cost: Duration,
+    reads: Cell<u64>,
+    memory: HashMap<u64, u8>,
+    fields: RefCell<Vec<(String, String)>>,
+}
+
+impl FakeGame {
+    fn new(read_cost: Duration) -> Self {
+        let mut game = Self {
+            read_cost,
+            reads: Cell::new(0),
+            memory: HashMap::new(),
+            fields: RefCell::new(Vec::new()),
+        };
+        game.write(STATIC_FIELD, &INSTANCE.to_le_bytes());
+        game
+    }
+
+    fn write(&mut self, address: u64, bytes: &[u8]) {
+        for (i, byte) in bytes.iter().enumerate() {
+            self.memory.insert(address + i as u64, *byte);
+        }
+    }
+
+    fn write_field(&mut self, class_name: &str, field_name: &str, value: u32) {
+        let offset = self
+            .field_offset(class_name, field_name)
+            .unwrap_or_default();
+        self.write(INSTANCE + offset as u64, &value.to_le_bytes());
+    }
+
+    fn fill(&self, address: u64, buf: &mut [u8]) {
+        self.reads.set(self.reads.get() + 1);
+        let start = Instant::now();
+        while start.elapsed() < self.read_cost {
+            std::hint::spin_loop();
+        }
+        for (i, byte) in buf.iter_mut().enumerate() {
+            *byte = self
+                .memory
+                .get(&(address + i as u64))
+                .copied()
+                .unwrap_or_default();
+        }
+    }
+}
+
+impl GameMemory for FakeGame {
+    fn read<T: CheckedBitPattern>(&self, address: u64) -> Option<T> {
+        let mut bytes = vec![0; mem::size_of::<T>()];
+        self.fill(address, &mut bytes);
+        bytemuck::checked::try_pod_read_unaligned(&bytes).ok()
+    }
+
+    fn read_into_buf(&self, address: u64, buf: &mut [u8]) -> bool {
+        self.fill(address, buf);
+        true
+    }
+
+    // a pointer path costs a read per step, the last one reads the instance
+    fn deref<T: CheckedBitPattern, const N: usize>(&self, _pointer: &UnityPointer<N>) -> Option<T> {
+        for _ in 1..N {
+            self.fill(STATIC_FIELD, &mut [0; 8]);
+        }
+        self.read(STATIC_FIELD)
+    }
+
+    // every field gets its own offset the first time it's asked for
+    fn field_offset(&self, class_name: &str, field_name: &str) -> Option<u32> {
+        let mut fields = self.fields.borrow_mut();
+        let index = match fields
+            .iter()
+            .position(|(class, field)| class == class_name && field == field_name)
+        {
+            Some(index) => index,
+            None => {
+                fields.push((class_name.to_string(), field_name.to_string()));
+                fields.len() - 1
+            }
+        };
+        Some(FIRST_FIELD_OFFSET + index as u32 * 8)
+    }
+}
+
+// update_watchers sets variables and logs, the runtime functions behind them only exist inside
+// LiveSplit so the benchmark stands in for them
+#[no_mangle]
+extern "C" fn timer_set_variable(
+    _key_ptr: *const u8,
+    _key_len: usize,
+    _value_ptr: *const u8,
+    _value_len: usize,
+) {
+}
+
+#[no_mangle]
+extern "C" fn runtime_print_message(_text_ptr: *const u8, _text_len: usize) {}
+
+// goes from the stage select into a boss stage, which has the most reads, and times the ticks in it
+fn run_mode(read_cost: Duration, timer_mode: TimerMode) -> (f64, u64) {
+    let mut game = FakeGame::new(read_cost);
+    let mut bench = WatcherBench::new(&game, timer_mode);
+
+    game.write_field(
+        "SceneManager",
+        "m_eCurrentScene",
+        GameStage::StageSelect as u32,
+    );
+    bench.tick(&game);
+    game.write_field(
+        "SceneManager",
+        "m_eCurrentScene",
+        GameStage::Stage1_4 as u32,
+    );
+    // playing
+    game.write_field("StageManager", "m_step", 3);
+    bench.tick(&game);
+
+    game.reads.set(0);
+    let start = Instant::now();
+    for _ in 0..TICKS {
+        bench.tick(&game);
+    }
+    let tick_micros = start.elapsed().as_secs_f64() * 1_000_000. / TICKS as f64;
+    (tick_micros, game.reads.get() / TICKS as u64)
+}
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1);
+    let budget = args
+        .next()
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(DEFAULT_BUDGET_MICROS);
+    let read_cost = args
+        .next()
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(DEFAULT_READ_COST_NANOS);
+
+    println!("{TICKS} ticks per mode, {read_cost}ns per read, budget {budget}µs per tick\n");
+
+    let mut over_budget = false;
+    for (timer_mode, name) in MODES {
+        let (tick_micros, reads) = run_mode(Duration::from_nanos(read_cost), timer_mode);
+        println!("{name:<24} {tick_micros:>8.1}µs/tick {reads:>4} reads/tick");
+        if tick_micros > budget {
+            println!("    over budget!");
+            over_budget = true;
+        }
+    }
+
+    match over_budget {
+        true => ExitCode::FAILURE,
+        false => ExitCode::SUCCESS,
+    }
+}
@@ -0,0 +1,164 @@
+// Turns the `TRACE|...` lines the auto splitter logs with the "Debug Trace" setting into a readable timeline.
+// Any other text in the log is ignored, so the whole debugger or LiveSplit log can be passed in.
+//
+// cargo run --features host-tools --bin trace_timeline --target <host triple> -- [log file]
+// Reads from stdin when no file is given.
+
+use std::{
+    env, fs,
+    io::{self, Read},
+};
+
+// has to stay in sync with `TraceRecorder::record`
+struct Frame<'a> {
+    tick: u64,
+    time: f64,
+    level: &'a str,
+    player_state: &'a str,
+    stage_state: &'a str,
+    time_trial_state: &'a str,
+    checkpoint: i32,
+    loading: bool,
+    timer_state: &'a str,
+    split_index: i64,
+}
+
+fn main() -> io::Result<()> {
+    let log = match env::args().nth(1) {
+        Some(path) => fs::read_to_string(path)?,
+        None => {
+            let mut log = String::new();
+            io::stdin().read_to_string(&mut log)?;
+            log
+        }
+    };
+
+    let mut previous: Option<Frame> = None;
+    let mut load_start = 0.;
+    let mut load_count = 0;
+    let mut load_total = 0.;
+
+    println!("{:>8}  {:>10}  {:<22}  events", "tick", "time", "level");
+    for frame in log.lines().filter_map(parse_line) {
+        let mut events = Vec::new();
+
+        match &previous {
+            None => events.push(format!(
+                "trace start: player {}, stage {}, trial {}, timer {}",
+                frame.player_state, frame.stage_state, frame.time_trial_state, frame.timer_state
+            )),
+            Some(old) => {
+                if old.tick > frame.tick {
+                    events.push("splitter restarted".to_string());
+                }
+                if old.level != frame.level {
+                    events.push(format!("level {} -> {}", old.level, frame.level));
+                }
+                if old.player_state != frame.player_state {
+                    events.push(format!(
+                        "player {} -> {}",
+                        old.player_state, frame.player_state
+                    ));
+                }
+                if old.stage_state != frame.stage_state {
+                    events.push(format!(
+                        "stage {} -> {}",
+                        old.stage_state, frame.stage_state
+                    ));
+                }
+                if old.time_trial_state != frame.time_trial_state {
+                    events.push(format!(
+                        "trial {} -> {}",
+                        old.time_trial_state, frame.time_trial_state
+                    ));
+                }
+                if old.checkpoint != frame.checkpoint {
+                    events.push(format!(
+                        "checkpoint {} -> {}",
+                        old.checkpoint, frame.checkpoint
+                    ));
+                }
+                if !old.loading && frame.loading {
+                    load_start = frame.time;
+                    events.push("load start".to_string());
+                }
+                if old.loading && !frame.loading {
+                    let load_time = frame.time - load_start;
+                    load_count += 1;
+                    load_total += load_time;
+                    events.push(format!("load end ({load_time:.3}s)"));
+                }
+                events.extend(timer_actions(old, &frame));
+            }
+        }
+
+        if !events.is_empty() {
+            println!(
+                "{:>8}  {:>9.3}s  {:<22}  {}",
+                frame.tick,
+                frame.time,
+                frame.level,
+                events.join(", ")
+            );
+        }
+        previous = Some(frame);
+    }
+
+    match previous {
+        Some(_) => println!("\n{load_count} loads, {load_total:.3}s in total"),
+        None => eprintln!("no TRACE lines found, is the \"Debug Trace\" setting enabled?"),
+    }
+
+    Ok(())
+}
+
+fn parse_line(line: &str) -> Option<Frame<'_>> {
+    let trace = &line[line.find("TRACE|")? + "TRACE|".len()..];
+    let fields: Vec<&str> = trace.trim_end().split('|').collect();
+    let [tick, time, level, player_state, stage_state, time_trial_state, checkpoint, loading, timer_state, split_index] =
+        fields[..]
+    else {
+        return None;
+    };
+
+    Some(Frame {
+        tick: tick.parse().ok()?,
+        time: time.parse().ok()?,
+        level,
+        player_state,
+        stage_state,
+        time_trial_state,
+        checkpoint: checkpoint.parse().ok()?,
+        loading: loading == "1",
+        timer_state,
+        split_index: split_index.parse().ok()?,
+    })
+}
+
+// the splitter doesn't log its actions, they are worked out from the timer state and split index
+fn timer_actions(old: &Frame, new: &Frame) -> Vec<String> {
+    let mut actions = Vec::new();
+
+    if old.timer_state != new.timer_state {
+        match new.timer_state {
+            "Running" if old.timer_state == "Not Running" => actions.push("START".to_string()),
+            "Running" if old.timer_state == "Paused" => actions.push("RESUME".to_string()),
+            "Paused" => actions.push("PAUSE".to_string()),
+            "Ended" => actions.push("FINISH".to_string()),
+            "Not Running" => actions.push("RESET".to_string()),
+            state => actions.push(format!("timer {} -> {state}", old.timer_state)),
+        }
+    }
+
+    // skips move the index too, they can only be told apart from splits when several happen in one tick
+    if new.split_index > old.split_index && old.split_index >= 0 && new.timer_state != "Ended" {
+        let moved = new.split_index - old.split_index;
+        let segment = new.split_index + 1;
+        match moved {
+            1 => actions.push(format!("SPLIT -> segment {segment}")),
+            _ => actions.push(format!("SPLIT/SKIP x{moved} -> segment {segment}")),
+        }
+    }
+
+    actions
+}
@@ -0,0 +1,37 @@
+#[cfg(target_os = "wasi")]
+use asr::time_util::Instant;
+
+// real time between ticks. the tick rate is only what the runtime is asked for, a late tick or the
+// adaptive tick rate changing it makes adding up 1 / tick rate drift away from the time that passed
+pub struct TickClock {
+    #[cfg(target_os = "wasi")]
+    start: Instant,
+    total: f64,
+}
+
+impl Default for TickClock {
+    fn default() -> Self {
+        Self {
+            #[cfg(target_os = "wasi")]
+            start: Instant::now(),
+            total: 0.,
+        }
+    }
+}
+
+impl TickClock {
+    // the runtime only has a clock for WASI builds, anything else counts the nominal tick time
+    #[cfg(target_os = "wasi")]
+    pub fn tick(&mut self, _nominal: f64) -> f64 {
+        let total = self.start.elapsed().as_seconds_f64();
+        let tick_time = total - self.total;
+        self.total = total;
+        tick_time
+    }
+
+    #[cfg(not(target_os = "wasi"))]
+    pub fn tick(&mut self, nominal: f64) -> f64 {
+        self.total += nominal;
+        nominal
+    }
+}
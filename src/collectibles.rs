@@ -0,0 +1,59 @@
+use crate::stages::{GameStage, STAGE_SAVE_ORDER};
+
+// one bit per stage in the save data order, there are fewer than 64 of them
+#[derive(Default)]
+pub struct CompletionTracker {
+    completed_stages: u64,
+}
+
+impl CompletionTracker {
+    pub fn reset(&mut self) {
+        self.completed_stages = 0;
+    }
+
+    // returns true only the first time a stage is completed in the run,
+    // so revisiting a stage that was already 100%'d doesn't trigger anything
+    pub fn complete_stage(&mut self, stage: GameStage) -> bool {
+        let Some(index) = stage.save_index().filter(|_| stage.has_collectibles()) else {
+            return false;
+        };
+
+        let bit = 1 << index;
+        if self.completed_stages & bit != 0 {
+            return false;
+        }
+        self.completed_stages |= bit;
+        true
+    }
+
+    pub fn completion_percentage(&self) -> f64 {
+        let stages = STAGE_SAVE_ORDER
+            .iter()
+            .filter(|stage| stage.has_collectibles())
+            .count();
+        self.completed_stages.count_ones() as f64 * 100. / stages as f64
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub struct StageCollectibles {
+    pub pac_dots: i32,
+    pub pac_dots_total: i32,
+    pub fruit: i32,
+    pub fruit_total: i32,
+    pub tokens: i32,
+    pub tokens_total: i32,
+    pub galaxian_pieces: i32,
+    pub galaxian_pieces_total: i32,
+}
+
+impl StageCollectibles {
+    pub fn is_complete(&self) -> bool {
+        // totals are 0 while the stage manager is still loading, don't count that as complete
+        self.pac_dots_total + self.fruit_total + self.tokens_total + self.galaxian_pieces_total > 0
+            && self.pac_dots >= self.pac_dots_total
+            && self.fruit >= self.fruit_total
+            && self.tokens >= self.tokens_total
+            && self.galaxian_pieces >= self.galaxian_pieces_total
+    }
+}
@@ -0,0 +1,73 @@
+use asr::watcher::Pair;
+
+use crate::{stages::GameStage, PlayerState};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum CutsceneKind {
+    // the movie scene, can be skipped
+    Movie,
+    // the in-level cut-ins, Pac-Man can't move and they can't be skipped
+    CutIn,
+}
+
+// skips aren't counted: the game has no skip flag, and the movie scene doesn't say which movie is
+// playing, so there is nothing to compare a movie's length against
+#[derive(Default)]
+pub struct CutsceneTracker {
+    current: Option<CutsceneKind>,
+    total: f64,
+    unskippable_total: f64,
+    count: u32,
+}
+
+impl CutsceneTracker {
+    pub fn reset(&mut self) {
+        self.total = 0.;
+        self.unskippable_total = 0.;
+        self.count = 0;
+    }
+
+    // `counting` is false while no run is going
+    pub fn update(
+        &mut self,
+        level_pair: Pair<GameStage>,
+        player_state: PlayerState,
+        counting: bool,
+        tick_time: f64,
+    ) -> Option<CutsceneKind> {
+        let cutscene = if level_pair.current == GameStage::Movie {
+            Some(CutsceneKind::Movie)
+        } else if matches!(player_state, PlayerState::CutIn | PlayerState::CutInGrap) {
+            Some(CutsceneKind::CutIn)
+        } else {
+            None
+        };
+
+        if cutscene != self.current {
+            if cutscene.is_some() && counting {
+                self.count += 1;
+            }
+            self.current = cutscene;
+        }
+
+        if let Some(kind) = cutscene.filter(|_| counting) {
+            self.total += tick_time;
+            if kind == CutsceneKind::CutIn {
+                self.unskippable_total += tick_time;
+            }
+        }
+        cutscene
+    }
+
+    pub fn total(&self) -> f64 {
+        self.total
+    }
+
+    pub fn unskippable_total(&self) -> f64 {
+        self.unskippable_total
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+}
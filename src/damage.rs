@@ -0,0 +1,127 @@
+use asr::watcher::Pair;
+
+use crate::PlayerState;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum DamageKind {
+    Hit,
+    Fall,
+    IcePool,
+    SnowBall,
+    Sink,
+}
+
+pub const DAMAGE_KINDS: [DamageKind; 5] = [
+    DamageKind::Hit,
+    DamageKind::Fall,
+    DamageKind::IcePool,
+    DamageKind::SnowBall,
+    DamageKind::Sink,
+];
+
+impl DamageKind {
+    fn from_player_state(state: PlayerState) -> Option<Self> {
+        match state {
+            PlayerState::Damage => Some(DamageKind::Hit),
+            PlayerState::FallDamage => Some(DamageKind::Fall),
+            PlayerState::IcePoolDamage => Some(DamageKind::IcePool),
+            PlayerState::SnowBallDamage => Some(DamageKind::SnowBall),
+            PlayerState::SinkDamage => Some(DamageKind::Sink),
+            _ => None,
+        }
+    }
+
+    pub fn to_string(self) -> &'static str {
+        match self {
+            DamageKind::Hit => "Hit",
+            DamageKind::Fall => "Fall",
+            DamageKind::IcePool => "Ice Pool",
+            DamageKind::SnowBall => "Snowball",
+            DamageKind::Sink => "Sink",
+        }
+    }
+
+    pub fn variable_name(self) -> &'static str {
+        match self {
+            DamageKind::Hit => "Hits: Hit",
+            DamageKind::Fall => "Hits: Fall",
+            DamageKind::IcePool => "Hits: Ice Pool",
+            DamageKind::SnowBall => "Hits: Snowball",
+            DamageKind::Sink => "Hits: Sink",
+        }
+    }
+
+    fn index(self) -> usize {
+        DAMAGE_KINDS
+            .iter()
+            .position(|kind| *kind == self)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum DamageEvent {
+    Damage(DamageKind),
+    Death,
+}
+
+// damage taken and deaths, for the run and for the stage being played
+#[derive(Default)]
+pub struct DamageTracker {
+    run: [u32; DAMAGE_KINDS.len()],
+    stage: [u32; DAMAGE_KINDS.len()],
+    run_deaths: u32,
+    stage_deaths: u32,
+}
+
+impl DamageTracker {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn reset_stage(&mut self) {
+        self.stage = [0; DAMAGE_KINDS.len()];
+        self.stage_deaths = 0;
+    }
+
+    pub fn update(&mut self, player_state_pair: Pair<PlayerState>) -> Option<DamageEvent> {
+        if !player_state_pair.changed() {
+            return None;
+        }
+
+        if player_state_pair.current == PlayerState::Dead {
+            self.run_deaths += 1;
+            self.stage_deaths += 1;
+            return Some(DamageEvent::Death);
+        }
+
+        let kind = DamageKind::from_player_state(player_state_pair.current)?;
+        // going from one damage state into another is the same hit
+        if DamageKind::from_player_state(player_state_pair.old).is_some() {
+            return None;
+        }
+        self.run[kind.index()] += 1;
+        self.stage[kind.index()] += 1;
+        Some(DamageEvent::Damage(kind))
+    }
+
+    pub fn hits(&self) -> u32 {
+        self.run.iter().sum()
+    }
+
+    pub fn stage_hits(&self) -> u32 {
+        self.stage.iter().sum()
+    }
+
+    pub fn hits_of(&self, kind: DamageKind) -> u32 {
+        self.run[kind.index()]
+    }
+
+    pub fn deaths(&self) -> u32 {
+        self.run_deaths
+    }
+
+    pub fn stage_deaths(&self) -> u32 {
+        self.stage_deaths
+    }
+}
@@ -0,0 +1,60 @@
+use asr::{
+    game_engine::unity::il2cpp::{Image, Module, UnityPointer, Version},
+    Process,
+};
+use bytemuck::CheckedBitPattern;
+
+// every read update_watchers does goes through this, so the real function can be run against a
+// fake game by the tick benchmark
+pub trait GameMemory {
+    fn read<T: CheckedBitPattern>(&self, address: u64) -> Option<T>;
+
+    // false when the whole buffer couldn't be read
+    fn read_into_buf(&self, address: u64, buf: &mut [u8]) -> bool;
+
+    fn deref<T: CheckedBitPattern, const N: usize>(&self, pointer: &UnityPointer<N>) -> Option<T>;
+
+    // from the class metadata, the offsets don't change while the game runs
+    fn field_offset(&self, class_name: &str, field_name: &str) -> Option<u32>;
+}
+
+// the game process with its il2cpp module
+pub struct Game<'a> {
+    process: &'a Process,
+    module: Module,
+    image: Image,
+}
+
+impl<'a> Game<'a> {
+    pub fn attach(process: &'a Process) -> Option<Self> {
+        let module = Module::attach(process, Version::V2020)?;
+        let image = module.get_default_image(process)?;
+        Some(Self {
+            process,
+            module,
+            image,
+        })
+    }
+}
+
+impl GameMemory for Game<'_> {
+    fn read<T: CheckedBitPattern>(&self, address: u64) -> Option<T> {
+        self.process.read::<T>(address).ok()
+    }
+
+    fn read_into_buf(&self, address: u64, buf: &mut [u8]) -> bool {
+        self.process.read_into_buf(address, buf).is_ok()
+    }
+
+    fn deref<T: CheckedBitPattern, const N: usize>(&self, pointer: &UnityPointer<N>) -> Option<T> {
+        pointer
+            .deref::<T>(self.process, &self.module, &self.image)
+            .ok()
+    }
+
+    fn field_offset(&self, class_name: &str, field_name: &str) -> Option<u32> {
+        self.image
+            .get_class(self.process, &self.module, class_name)?
+            .get_field_offset(self.process, &self.module, field_name)
+    }
+}
@@ -0,0 +1,43 @@
+use asr::{
+    time::Duration,
+    timer::{self, TimerState},
+};
+
+// the timer's game time, the runtime can only set it so it's counted here alongside the pauses.
+// every pause and resume has to go through this for the count to stay right
+#[derive(Default)]
+pub struct GameTime {
+    time: f64,
+    paused: bool,
+}
+
+impl GameTime {
+    pub fn pause(&mut self) {
+        timer::pause_game_time();
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        timer::resume_game_time();
+        self.paused = false;
+    }
+
+    // for the modes that start the game time from 0 themselves
+    pub fn restart(&mut self) {
+        timer::set_game_time(Duration::seconds(0));
+        self.time = 0.;
+    }
+
+    // after the pauses of the tick
+    pub fn update(&mut self, tick_time: f64) {
+        match timer::state() {
+            TimerState::NotRunning => self.time = 0.,
+            TimerState::Running if !self.paused => self.time += tick_time,
+            _ => {}
+        }
+    }
+
+    pub fn current(&self) -> f64 {
+        self.time
+    }
+}
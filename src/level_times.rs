@@ -0,0 +1,81 @@
+use asr::watcher::Pair;
+
+use crate::{
+    stages::{GameStage, StageKind, STAGE_SAVE_ORDER},
+    totals::Totals,
+};
+
+// after the levels, a slot for the stage select and pac-village and one for the menus and movies
+const HUB_SLOT: usize = STAGE_SAVE_ORDER.len();
+const OTHER_SLOT: usize = STAGE_SAVE_ORDER.len() + 1;
+const SLOTS: usize = STAGE_SAVE_ORDER.len() + 2;
+
+// game time spent in every level of a full game run, to compare the sections with IL times.
+// a level played more than once adds up, the current and last level times are for a single visit
+#[derive(Default)]
+pub struct LevelTimes {
+    totals: Totals<SLOTS>,
+    current: f64,
+    level: Option<GameStage>,
+    last_level: Option<(GameStage, f64)>,
+}
+
+impl LevelTimes {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    // `level` is the parent level of the scene, so a maze or bonus stage adds to the level it's part of.
+    // `time` is how much the run's game time went up this tick, by whichever timing method is picked
+    pub fn update(&mut self, scene_pair: Pair<GameStage>, level: Option<GameStage>, time: f64) {
+        if level != self.level || (level.is_none() && scene_pair.changed()) {
+            if let Some(old_level) = self.level.filter(|_| self.current > 0.) {
+                self.last_level = Some((old_level, self.current));
+            }
+            self.level = level;
+            self.current = 0.;
+        }
+
+        self.current += time;
+        self.totals[level_slot(scene_pair.current, level)] += time;
+    }
+
+    pub fn current(&self) -> f64 {
+        self.current
+    }
+
+    pub fn last_level(&self) -> Option<(GameStage, f64)> {
+        self.last_level
+    }
+
+    pub fn hub_total(&self) -> f64 {
+        self.totals[HUB_SLOT]
+    }
+
+    pub fn print_summary(&self) {
+        asr::print_message("Time per level:");
+        for (stage, total) in STAGE_SAVE_ORDER.iter().zip(self.totals.iter()) {
+            if *total > 0. {
+                asr::print_limited::<128>(&format_args!("  {}: {:.3}s", stage.to_string(), total));
+            }
+        }
+        asr::print_limited::<128>(&format_args!(
+            "  Stage select and Pac-Village: {:.3}s",
+            self.totals[HUB_SLOT]
+        ));
+        let other = self.totals[OTHER_SLOT];
+        if other > 0. {
+            asr::print_limited::<128>(&format_args!("  Menus and movies: {other:.3}s"));
+        }
+    }
+}
+
+fn level_slot(scene: GameStage, level: Option<GameStage>) -> usize {
+    if let Some(index) = level.and_then(GameStage::save_index) {
+        return index;
+    }
+    match scene.kind() {
+        StageKind::StageSelect | StageKind::Hub => HUB_SLOT,
+        _ => OTHER_SLOT,
+    }
+}
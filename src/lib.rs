@@ -1,7 +1,29 @@
 #![no_std]
 
+// the real update_watchers for the host benchmark, with a fake game behind it
+#[cfg(feature = "host-tools")]
+pub mod benchmark;
+mod bests;
+mod clock;
+mod collectibles;
+mod cutscenes;
+mod damage;
+mod game;
+mod game_time;
+mod level_times;
+mod load_policy;
+mod load_removal;
+mod load_stats;
 mod memory;
+mod profiler;
+mod progress;
+mod singleton;
+mod stage_clock;
 mod stages;
+mod text;
+mod time_scale;
+mod totals;
+mod trace;
 
 use asr::{
     future::{next_tick, retry},
@@ -11,14 +33,43 @@ use asr::{
     watcher::{Pair, Watcher},
     Process,
 };
-use memory::{update_watchers, Memory};
-use stages::GameStage;
+use bests::{BestKey, BestKind, Bests};
+use clock::TickClock;
+use collectibles::CompletionTracker;
+use cutscenes::{CutsceneKind, CutsceneTracker};
+use damage::{DamageEvent, DamageTracker, DAMAGE_KINDS};
+use game::Game;
+use game_time::GameTime;
+use level_times::LevelTimes;
+use load_policy::{LoadClassifier, LoadKind};
+use load_removal::LoadDetector;
+use load_stats::LoadStats;
+use memory::{
+    player_state_to_string, stage_state_to_string, time_trial_state_to_string, update_watchers,
+    Memory,
+};
+use stage_clock::StageClock;
+use stages::{GameStage, ParentLevel, StageKind};
+use text::Text;
+use time_scale::{is_legal_time_scale, TimeScaleMeter};
+use trace::{TraceFrame, TraceRecorder};
 
 asr::async_main!(stable);
 asr::panic_handler!();
 
+// the rate is only what the runtime is asked for, the time between ticks is measured by the tick clock
+// high is for loads, goals, time trials and the title, low for the menus and stage select when no run is going
+const HIGH_TICK_RATE: f64 = 120.;
+const NORMAL_TICK_RATE: f64 = 60.;
+const LOW_TICK_RATE: f64 = 20.;
+
+// the difficulty select animation before the first load, it's added manually on the full game start
+const FULL_GAME_START_OFFSET: Duration = Duration::new(3, 433_333_333);
+
 async fn main() {
     let mut settings = Settings::register();
+    let mut tick_rate = HIGH_TICK_RATE;
+    asr::set_tick_rate(tick_rate);
 
     asr::print_message("PACMAN REPAC TWOOOOOOO autosplitter loaded");
 
@@ -32,22 +83,90 @@ async fn main() {
 
                 let mut enable_il_restart = false;
                 let mut enable_level_split = false;
+                // The current level already split, it can't split again until the next level.
+                let mut level_split_done = false;
+                // Whether the save already had the current level cleared when the player entered it.
+                let mut revisiting_level = false;
                 let mut last_time_trial_split_time: f64 = 0.;
                 let mut highest_boss_phase_split = 0;
+                let mut lowest_boss_hp_split = u32::MAX;
 
                 // Track whether the player has cleared a level in an IL series.
                 // Prevents resetting the run once the player enters their second level or later.
                 let mut il_series_first_goal_clear = false;
+                // The level was completed but the split timing point wasn't reached yet.
+                let mut il_series_level_end_pending = false;
+                let mut il_series_level_split_done = false;
+
+                // Stages that were 100%'d during the run, only the first completion of each stage can split.
+                let mut completion_tracker = CompletionTracker::default();
 
                 let mut time_trial_marathon_timer_acum: f64 = 0.;
                 let mut restarting_level = false;
 
+                // In-game stage clock summed over the run, and our own count of the load removed time to compare it with.
+                let mut stage_clock = StageClock::default();
+                let mut load_removed_time: f64 = 0.;
+
+                // Loads removed from the current run, the summary is printed once when the run ends.
+                let mut load_stats = LoadStats::default();
+                let mut level_times = LevelTimes::default();
+                // The level a maze or bonus stage belongs to, for the per level stats.
+                let mut parent_level_tracker = ParentLevel::default();
+                let mut run_summary_printed = false;
+
+                // Frame rate independent load detection, the loading bar animation is timed instead of read per frame.
+                let mut load_detector = LoadDetector::default();
+                let mut load_classifier = LoadClassifier::default();
+
+                // Cutscene time of the current run.
+                let mut cutscene_tracker = CutsceneTracker::default();
+
+                // The game time the timer shows in the modes that pause it, what the individual level bests are kept in.
+                let mut game_time = GameTime::default();
+
+                // Load and cutscene removal on individual levels, the time is only resumed after something it paused itself.
+                let mut il_removal_paused = false;
+                let mut il_series_between_levels = false;
+
+                // Checkpoint the practiced segment starts from, -1 is the level start.
+                let mut segment_start_checkpoint: i32 = -1;
+                // Where the segment ended, None for the goal.
+                let mut segment_end_checkpoint: Option<i32> = Some(0);
+                // The player died or restarted from the pause menu, the segment restarts on control.
+                let mut segment_respawning = false;
+
+                // Best times kept between sessions.
+                let mut bests = Bests::default();
+
+                // The intro the current level was entered with, until the player gets control.
+                let mut level_intro: Option<PlayerState> = None;
+
+                let mut trace_recorder = TraceRecorder::default();
+
+                // Run validity, a modified time scale or the debug pause make a run invalid for the leaderboards.
+                let mut time_scale_meter = TimeScaleMeter::default();
+                let mut time_scale_legal = true;
+                let mut debug_pause_used = false;
+
+                // Game overs in the current run, a game over is only counted once until the player is back in control.
+                let mut game_over_count: u32 = 0;
+                let mut in_game_over = false;
+
+                // Damage taken in the run and the current stage, and the stage a hitless or deathless run failed on.
+                let mut damage_tracker = DamageTracker::default();
+                let mut challenge_failed_on: Option<GameStage> = None;
+
                 // Perform memory scanning to look for the addresses we need
-                let mut memory = retry(|| Memory::init(&process)).await;
+                let game = retry(|| Game::attach(&process)).await;
+                let mut memory = Memory::init(&game);
+                let mut tick_clock = TickClock::default();
                 loop {
                     // MAIN LOOP
                     settings.update();
-                    update_watchers(&process, &mut memory, &mut watchers, &settings);
+                    update_watchers(&game, &mut memory, &mut watchers, (&settings).into());
+                    // time since the previous tick
+                    let tick_time = tick_clock.tick(1. / tick_rate);
 
                     // get memory values
                     let is_loading_pair = watchers.is_loading.pair.unwrap_or_default();
@@ -61,54 +180,352 @@ async fn main() {
                     let load_ui_progress_pair =
                         watchers.load_ui_progress.pair.unwrap_or(Pair::default());
                     let player_state_pair = watchers.player_state.pair.unwrap_or_default();
+                    let intro_ended = level_intro_ended(&mut level_intro, &watchers);
+
+                    // only the load types picked in the settings are removed
+                    let load_detected = load_detector.update(
+                        is_loading_pair.current,
+                        load_ui_progress_pair.current,
+                        tick_time,
+                    );
+                    let load_kind = load_classifier.update(
+                        load_detected,
+                        watchers.level_id.pair.unwrap_or_default(),
+                        watchers.stage_state.pair.unwrap_or_default(),
+                        player_state_has_control(player_state_pair.current),
+                    );
+                    let loading = load_kind.is_some_and(|kind| load_removed(&settings, kind));
+                    if let Some(kind) = load_kind {
+                        timer::set_variable("Load Type", kind.to_string());
+                    }
 
+                    if timer::state() == TimerState::NotRunning {
+                        cutscene_tracker.reset();
+                        game_over_count = 0;
+                        damage_tracker.reset();
+                        challenge_failed_on = None;
+                    }
+                    let cutscene = cutscene_tracker.update(
+                        watchers.level_id.pair.unwrap_or_default(),
+                        player_state_pair.current,
+                        timer::state() == TimerState::Running,
+                        tick_time,
+                    );
+                    // the cut-ins can't be skipped, some leaderboards time without them
+                    let cutscene_removed = settings.remove_unskippable_cutscenes
+                        && cutscene == Some(CutsceneKind::CutIn);
+                    timer::set_variable_float("Cutscene Time", cutscene_tracker.total());
+                    timer::set_variable_float(
+                        "Unskippable Cutscene Time",
+                        cutscene_tracker.unskippable_total(),
+                    );
+                    timer::set_variable_int("Cutscenes", cutscene_tracker.count());
 
                     // Reset goal flag
                     if !(timer::state() == TimerState::Running || timer::state() == TimerState::Paused) {
                         il_series_first_goal_clear = false;
+                        il_series_level_end_pending = false;
+                        il_series_level_split_done = false;
+                        il_series_between_levels = false;
+                        completion_tracker.reset();
+                        debug_pause_used = false;
+                    }
+
+                    let stage_complete_pair = watchers.stage_complete.pair.unwrap_or_default();
+                    let first_stage_completion = stage_complete_pair.changed()
+                        && stage_complete_pair.current
+                        && completion_tracker
+                            .complete_stage(watchers.level_id.pair.unwrap_or_default().current);
+                    let split_stage_completion =
+                        first_stage_completion && settings.split_stage_complete;
+                    timer::set_variable_float(
+                        "Completion %",
+                        completion_tracker.completion_percentage(),
+                    );
+
+                    let level = watchers.level_id.pair.unwrap_or_default().current;
+                    let stage_state_pair = watchers.stage_state.pair.unwrap_or_default();
+                    // the stage clock only runs steadily while the player has control
+                    let stage_clock_steady =
+                        matches!(stage_state_pair.current, StageState::Playing | StageState::Maze)
+                            && player_state_has_control(player_state_pair.current);
+                    let stage_time = watchers.stage_time.pair.unwrap_or_default().current as f64;
+                    if let Some(scale) = time_scale_meter
+                        .update(stage_clock_steady.then_some(stage_time), tick_time)
+                    {
+                        timer::set_variable_float("Stage Clock Rate (Estimate)", scale);
+                        if time_scale_legal && !is_legal_time_scale(scale) {
+                            asr::print_limited::<128>(&format_args!(
+                                "Warning: the stage clock ran at {:.2}x real time on {}, the time scale may be changed",
+                                scale,
+                                level.to_string()
+                            ));
+                        }
+                        time_scale_legal = is_legal_time_scale(scale);
                     }
 
+                    if stage_state_pair.changed() && stage_state_pair.current == StageState::DebugPause
+                    {
+                        asr::print_limited::<128>(&format_args!(
+                            "Warning: debug pause used on {}",
+                            level.to_string()
+                        ));
+                        debug_pause_used = true;
+                    }
+                    timer::set_variable(
+                        "Debug Pause",
+                        match debug_pause_used {
+                            true => "Used",
+                            false => "Not Used",
+                        },
+                    );
+
+                    let game_over = game_over_started(&watchers, &mut in_game_over);
+                    if let Some(stage) = game_over {
+                        if timer::state() == TimerState::Running {
+                            game_over_count += 1;
+                        }
+                        asr::print_limited::<128>(&format_args!(
+                            "Game over on {}, {} this run",
+                            stage.to_string(),
+                            game_over_count
+                        ));
+                        // practice runs start over, a full game keeps going from the save
+                        if settings.reset_on_game_over
+                            && matches!(
+                                settings.timer_mode.current,
+                                TimerMode::IL | TimerMode::ILSeries
+                            )
+                        {
+                            timer::reset();
+                        }
+                    }
+                    timer::set_variable_int("Game Overs", game_over_count);
+
+                    if watchers.level_id.pair.unwrap_or_default().changed() {
+                        damage_tracker.reset_stage();
+                    }
+                    let damage = damage_tracker.update(player_state_pair);
+                    if let Some(event) = damage {
+                        if timer::state() == TimerState::Running
+                            && challenge_failed_on.is_none()
+                            && challenge_fails(settings.challenge, event)
+                        {
+                            asr::print_limited::<128>(&format_args!(
+                                "{} failed on {}: {}",
+                                settings.challenge.to_string(),
+                                level.to_string(),
+                                match event {
+                                    DamageEvent::Damage(kind) => kind.to_string(),
+                                    DamageEvent::Death => "Death",
+                                }
+                            ));
+                            match settings.challenge_fail_action {
+                                ChallengeFailAction::Reset => timer::reset(),
+                                ChallengeFailAction::MarkFailed => {
+                                    challenge_failed_on = Some(level)
+                                }
+                            }
+                        }
+                    }
+                    timer::set_variable_int("Hits", damage_tracker.hits());
+                    timer::set_variable_int("Hits (Stage)", damage_tracker.stage_hits());
+                    for kind in DAMAGE_KINDS {
+                        timer::set_variable_int(kind.variable_name(), damage_tracker.hits_of(kind));
+                    }
+                    timer::set_variable_int("Deaths", damage_tracker.deaths());
+                    timer::set_variable_int("Deaths (Stage)", damage_tracker.stage_deaths());
+                    timer::set_variable(
+                        "Challenge",
+                        match (settings.challenge, challenge_failed_on) {
+                            (ChallengeMode::Off, _) => "Off",
+                            (_, Some(_)) => "Failed",
+                            (_, None) => "OK",
+                        },
+                    );
+                    if let Some(stage) = challenge_failed_on {
+                        timer::set_variable("Challenge Failed On", stage.to_string());
+                    }
+
+                    // the time of a stage, segment or time trial finished this tick
+                    let mut completed_time: Option<f64> = None;
+
                     match settings.timer_mode.current {
                         TimerMode::FullGame => {
-                            if is_loading_pair.current
-                                || (load_ui_progress_pair.current > 0.0
-                                    && load_ui_progress_pair.current < 1.0)
-                            {
-                                timer::pause_game_time();
-                            } else {
-                                timer::resume_game_time();
+                            match settings.full_game_timing {
+                                FullGameTiming::LoadRemoved => {
+                                    if loading || cutscene_removed {
+                                        game_time.pause();
+                                    } else {
+                                        game_time.resume();
+                                    }
+                                }
+                                // game time is set from the stage clock below
+                                FullGameTiming::InGameTime => game_time.pause(),
+                            }
+
+                            let time_counting = timer::state() == TimerState::Running
+                                && !loading
+                                && !cutscene_removed;
+                            if time_counting {
+                                load_removed_time += tick_time;
                             }
 
+                            // the game time the timer was given, only the run going on can be guarded
+                            let run_game_time = match settings.full_game_timing {
+                                FullGameTiming::LoadRemoved => load_removed_time,
+                                FullGameTiming::InGameTime => stage_clock.total(),
+                            };
+                            let run_going = matches!(
+                                timer::state(),
+                                TimerState::Running | TimerState::Paused
+                            );
+                            let reset_blocked = settings.reset_on_file_creation
+                                && run_going
+                                && reset_guard_active(&settings, run_game_time);
+
                             if start(&watchers, &settings) {
-                                if settings.reset_on_file_creation {
-                                    timer::reset();
+                                if reset_blocked {
+                                    asr::print_limited::<128>(&format_args!(
+                                        "New File reset blocked, the run is at split {} and {:.0} seconds",
+                                        timer::current_split_index().unwrap_or_default(),
+                                        run_game_time
+                                    ));
+                                    timer::set_variable("Reset Guard", "Blocked a New File reset");
+                                } else if settings.reset_on_file_creation
+                                    || timer::state() == TimerState::NotRunning
+                                {
+                                    // without the reset a New File during a run leaves the run alone
+                                    if settings.reset_on_file_creation {
+                                        timer::reset();
+                                    }
+                                    timer::set_variable("Reset Guard", "OK");
+                                    timer::start();
+                                    stage_clock.reset();
+                                    enable_level_split = false;
+                                    level_split_done = false;
+                                    load_stats.reset();
+                                    level_times.reset();
+                                    run_summary_printed = false;
+                                    // timing starts on difficulty select so we manually add the animation time before the loading starts
+                                    // if i manage to detect that from memory then this will be removed
+                                    load_removed_time = FULL_GAME_START_OFFSET.as_seconds_f64();
+                                    match settings.full_game_timing {
+                                        FullGameTiming::LoadRemoved => {
+                                            timer::set_game_time(FULL_GAME_START_OFFSET)
+                                        }
+                                        FullGameTiming::InGameTime => {
+                                            timer::set_game_time(Duration::seconds(0))
+                                        }
+                                    }
                                 }
-                                timer::start();
-                                // timing starts on difficulty select so we manually add the animation time before the loading starts
-                                // if i manage to detect that from memory then this will be removed
-                                timer::set_game_time(Duration::new(3, 433_333_333));
+                            }
+
+                            let level_pair = watchers.level_id.pair.unwrap_or_default();
+                            let parent_level = parent_level_tracker.update(level_pair.current);
+                            let stage_state = watchers.stage_state.pair.unwrap_or_default().current;
+                            let stage_time = watchers.stage_time.pair.unwrap_or_default().current;
+                            let stage_clock_before = stage_clock.total();
+                            stage_clock.update(
+                                level_pair.changed(),
+                                matches!(stage_state, StageState::Playing | StageState::Maze)
+                                    .then_some(stage_time as f64),
+                            );
+                            if settings.full_game_timing == FullGameTiming::InGameTime
+                                && timer::state() == TimerState::Running
+                            {
+                                timer::set_game_time(Duration::seconds_f64(stage_clock.total()));
+                            }
+                            timer::set_variable_float("In-Game Time", stage_clock.total());
+                            timer::set_variable_float(
+                                "Load-removed vs In-Game Time",
+                                load_removed_time - stage_clock.total(),
+                            );
+
+                            if level_pair.changed() && !level_pair.current.is_stage_select() {
+                                revisiting_level = watchers
+                                    .progress
+                                    .pair
+                                    .unwrap_or_default()
+                                    .old
+                                    .is_stage_cleared(level_pair.current);
                             }
 
                             // only do level splits if player actually completed the level
                             // or it's from pac-village
-                            if !enable_level_split {
-                                enable_level_split = enable_full_game_level_splits(&watchers);
+                            // backtracking to a level that was already cleared never splits
+                            // the completion is latched for the level, so it splits exactly once
+                            if !enable_level_split && !level_split_done {
+                                enable_level_split = enable_full_game_level_splits(&watchers)
+                                    && level_pair.current.final_boss().is_none()
+                                    && !(settings.split_first_clear_only && revisiting_level);
                             }
 
-                            if split_full_game(&watchers, &settings, enable_level_split) {
+                            let level_split =
+                                split_full_game(&watchers, &settings, enable_level_split);
+                            if level_split {
                                 timer::split();
+                                level_split_done |= enable_level_split;
                                 enable_level_split = false;
                             }
+
+                            // the latch is only cleared when the next level is entered from the
+                            // stage select, a completion that didn't split doesn't carry over to it
+                            if entered_level_from_stage_select(level_pair) {
+                                enable_level_split = false;
+                                level_split_done = false;
+                            }
+
+                            // the level split already covers a completion in the same tick
+                            if split_stage_completion && !level_split {
+                                timer::split();
+                            }
+
+                            if timer::state() == TimerState::Running {
+                                load_stats.update(loading, parent_level, tick_time);
+                            }
+                            timer::set_variable_float("Loads Removed", load_stats.total());
+                            timer::set_variable_float(
+                                "Loads Removed (Level)",
+                                load_stats.level_total(parent_level),
+                            );
+                            timer::set_variable_int("Load Count", load_stats.count());
+                            timer::set_variable_float("Longest Load", load_stats.longest());
+
+                            // the level times add up to the run's game time
+                            let game_time_added = match settings.full_game_timing {
+                                FullGameTiming::LoadRemoved if time_counting => tick_time,
+                                FullGameTiming::InGameTime
+                                    if timer::state() == TimerState::Running =>
+                                {
+                                    stage_clock.total() - stage_clock_before
+                                }
+                                _ => 0.,
+                            };
+                            level_times.update(level_pair, parent_level, game_time_added);
+                            timer::set_variable_float("Level Time", level_times.current());
+                            if let Some((last_level, last_time)) = level_times.last_level() {
+                                timer::set_variable("Last Level", last_level.to_string());
+                                timer::set_variable_float("Last Level Time", last_time);
+                            }
+                            timer::set_variable_float("Stage Select Time", level_times.hub_total());
+
+                            if timer::state() == TimerState::Ended && !run_summary_printed {
+                                load_stats.print_summary();
+                                level_times.print_summary();
+                                run_summary_printed = true;
+                            }
                         },
                         TimerMode::ILSeries => {
-                            if enable_reset_il(&watchers) {
+                            if enable_reset_il(&watchers, intro_ended) {
                                 enable_il_restart = true;
                             }
 
                             // Only reset on level start if the player hasn't completed a level yet in this run.
                             if player_gained_control(&watchers) && enable_il_restart
                             {
-                                timer::resume_game_time();
+                                game_time.resume();
+                                il_series_between_levels = false;
                                 if !il_series_first_goal_clear {
                                     if settings.reset_on_level_start {
                                         timer::reset();
@@ -116,7 +533,7 @@ async fn main() {
                                     if settings.start_il {
                                         if timer::state() != TimerState::Running {
                                             timer::start();
-                                            timer::set_game_time(Duration::seconds(0));
+                                            game_time.restart();
                                         }
                                     }
                                 }
@@ -129,18 +546,49 @@ async fn main() {
                             if hit_goal || beat_spooky(&watchers) || beat_toc_man(&watchers) {
                                 il_series_first_goal_clear = true;
                             }
-
-                            let split_on_level_end = settings.split_il && hit_goal;
-                            if split_on_level_end || split_final_boss(&watchers, &settings)
+                            if (hit_goal || stage_hit_goal(&watchers))
+                                && !il_series_level_split_done
+                                && level.final_boss().is_none()
                             {
+                                il_series_level_end_pending = true;
+                            }
+
+                            let split_on_level_end = settings.split_il
+                                && il_series_level_end_pending
+                                && level_end_reached(&watchers, settings.il_series_split_point);
+                            if split_on_level_end {
+                                il_series_level_end_pending = false;
+                                il_series_level_split_done = true;
+                            }
+                            if entered_level_from_stage_select(
+                                watchers.level_id.pair.unwrap_or_default(),
+                            ) {
+                                il_series_level_end_pending = false;
+                                il_series_level_split_done = false;
+                            }
+                            let level_split =
+                                split_on_level_end || split_final_boss(&watchers, &settings);
+                            if level_split {
+                                timer::split();
+                                game_time.pause();
+                                il_series_between_levels = true;
+                            }
+
+                            remove_time_il(
+                                (settings.remove_loads_il && loading) || cutscene_removed,
+                                &mut il_removal_paused,
+                                il_series_between_levels,
+                                &mut game_time,
+                            );
+
+                            if split_stage_completion && !level_split {
                                 timer::split();
-                                timer::pause_game_time();
                             }
                         }
                         TimerMode::IL => {
                             let checkpoint_pair = watchers.checkpoint.pair.unwrap_or_default();
                             let boss_phase_pair = watchers.boss_state.pair.unwrap_or_default();
-                            if enable_reset_il(&watchers) {
+                            if enable_reset_il(&watchers, intro_ended) {
                                 enable_il_restart = true;
                             }
 
@@ -148,18 +596,30 @@ async fn main() {
                             {
                                 if settings.reset_on_level_start {
                                     timer::reset();
-                                    timer::resume_game_time();
+                                    game_time.resume();
                                 }
                                 if settings.start_il {
                                     if timer::state() != TimerState::Running {
                                         timer::start();
-                                        timer::set_game_time(Duration::seconds(0));
+                                        game_time.restart();
                                     }
                                     highest_boss_phase_split = 0;
+                                    lowest_boss_hp_split = u32::MAX;
                                 }
                                 enable_il_restart = false;
                             }
 
+                            if settings.remove_pause_time_il {
+                                remove_pause_time(&watchers, &mut game_time);
+                            }
+
+                            remove_time_il(
+                                (settings.remove_loads_il && loading) || cutscene_removed,
+                                &mut il_removal_paused,
+                                false,
+                                &mut game_time,
+                            );
+
                             if player_hit_goal(&watchers) && settings.split_il
                             {
                                 // JANK SOLUTION to finish the run even when there are splits pending from skipping checkpoints
@@ -170,18 +630,84 @@ async fn main() {
                                 timer::split();
                             }
 
+                            let boss_hp_split =
+                                split_boss_hp(&watchers, &settings, &mut lowest_boss_hp_split);
                             if split_checkpoints(&checkpoint_pair, &settings)
                                 || split_boss_phase(
                                     &boss_phase_pair,
                                     &settings,
                                     &mut highest_boss_phase_split,
                                 )
+                                || boss_hp_split
+                                || split_stage_completion
                             {
                                 timer::split();
                             }
                         }
+                        TimerMode::Segment => {
+                            let checkpoint_pair = watchers.checkpoint.pair.unwrap_or_default();
+                            let passed_checkpoint =
+                                checkpoint_pair.changed() && !checkpoint_pair.decreased();
+
+                            if settings.remove_pause_time_il {
+                                remove_pause_time(&watchers, &mut game_time);
+                            }
+
+                            // a death or a pause menu restart, damage and cut-ins don't count
+                            let stage_state_pair = watchers.stage_state.pair.unwrap_or_default();
+                            if (stage_state_pair.changed()
+                                && stage_state_pair.current == StageState::PacDead)
+                                || (player_state_pair.changed()
+                                    && player_state_pair.current == PlayerState::Dead)
+                            {
+                                segment_respawning = true;
+                            }
+                            let respawned = intro_ended
+                                || (segment_respawning && player_gained_control(&watchers));
+
+                            // the segment ends on the next checkpoint or the goal
+                            let hit_goal = player_hit_goal(&watchers);
+                            if timer::state() == TimerState::Running
+                                && (hit_goal
+                                    || (passed_checkpoint
+                                        && checkpoint_pair.current > segment_start_checkpoint))
+                            {
+                                segment_end_checkpoint =
+                                    (!hit_goal).then_some(checkpoint_pair.current);
+                                // JANK SOLUTION to finish the run even when there are splits pending
+                                for _ in 0..100 {
+                                    timer::skip_split();
+                                }
+                                timer::split();
+                                completed_time = Some(game_time.current());
+                            } else if respawned
+                                || (passed_checkpoint && timer::state() == TimerState::NotRunning)
+                            {
+                                // every respawn starts the segment from the checkpoint the player is at
+                                segment_respawning = false;
+                                // a new start is compared with the segment to the next checkpoint
+                                // until it ends, the same start keeps the end it had
+                                if checkpoint_pair.current != segment_start_checkpoint {
+                                    segment_end_checkpoint = Some(checkpoint_pair.current + 1);
+                                }
+                                timer::reset();
+                                timer::start();
+                                game_time.restart();
+                                game_time.resume();
+                                segment_start_checkpoint = checkpoint_pair.current;
+                            }
+
+                            match segment_start_checkpoint {
+                                -1 => timer::set_variable("Segment Start", "Level Start"),
+                                checkpoint => timer::set_variable(
+                                    "Segment Start",
+                                    Text::<16>::format(format_args!("Checkpoint {checkpoint}"))
+                                        .as_str(),
+                                ),
+                            }
+                        }
                         TimerMode::TimeTrial => {
-                            timer::pause_game_time();
+                            game_time.pause();
                             let checkpoint_pair = watchers.checkpoint.pair.unwrap_or_default();
                             let boss_phase_pair = watchers.boss_state.pair.unwrap_or_default();
                             let stage_pair = watchers.level_id.pair.unwrap_or_default();
@@ -202,6 +728,7 @@ async fn main() {
                                 timer::start();
                                 last_time_trial_split_time = 0.;
                                 highest_boss_phase_split = 0;
+                                lowest_boss_hp_split = u32::MAX;
                             }
 
                             if time_trial_state_pair.old != TimeTrialState::End
@@ -212,6 +739,10 @@ async fn main() {
                                     timer::skip_split();
                                 }
                                 timer::split();
+                                completed_time = Some(match settings.time_trial_discount_bonus {
+                                    true => igt_with_bonus,
+                                    false => time_trial_igt_pair.current,
+                                });
                             }
 
                             if split_checkpoints(&checkpoint_pair, &settings) {
@@ -227,11 +758,14 @@ async fn main() {
                                 }
                             }
 
+                            let boss_hp_split =
+                                split_boss_hp(&watchers, &settings, &mut lowest_boss_hp_split);
                             if split_boss_phase(
                                 &boss_phase_pair,
                                 &settings,
                                 &mut highest_boss_phase_split,
-                            ) {
+                            ) || boss_hp_split
+                            {
                                 timer::split();
                             }
 
@@ -239,27 +773,27 @@ async fn main() {
                             if (time_trial_state_pair.current == TimeTrialState::None
                                 && time_trial_igt_pair.current != time_trial_igt_pair.old)
                                 || (stage_pair.changed()
-                                    && level_is_stage_select(stage_pair.current))
+                                    && stage_pair.current.is_stage_select())
                             {
                                 timer::reset();
                             }
                         }
                         TimerMode::TimeTrialMarathon => {
-                            timer::pause_game_time();
+                            game_time.pause();
                             let stage_pair = watchers.level_id.pair.unwrap_or_default();
                             let stage_state_pair = watchers.stage_state.pair.unwrap_or_default();
                             let boss_state = watchers.boss_state.pair.unwrap_or_default();
 
                             if time_trial_state_pair.current != time_trial_state_pair.old
                                 && time_trial_state_pair.current == TimeTrialState::TA
-                                && player_state_pair.current == PlayerState::Control
+                                && player_state_has_control(player_state_pair.current)
                                 && timer::state() == TimerState::NotRunning
                             {
                                 time_trial_marathon_timer_acum = 0.;
                                 timer::start();
                             }
 
-                            if restarting_level && player_state_pair.current == PlayerState::Control
+                            if restarting_level && player_state_has_control(player_state_pair.current)
                             {
                                 restarting_level = false;
                             }
@@ -309,6 +843,7 @@ async fn main() {
 
                             if level_ended {
                                 timer::split();
+                                completed_time = Some(current_igt_with_bonus);
                             }
 
                             timer::set_variable_float(
@@ -318,6 +853,81 @@ async fn main() {
                         }
                     }
 
+                    game_time.update(tick_time);
+
+                    // the stage clock of a goal touch in the modes that time more than one level,
+                    // the other modes give the time their timer shows
+                    let best_kind = match settings.timer_mode.current {
+                        TimerMode::FullGame | TimerMode::ILSeries => {
+                            if player_hit_goal(&watchers)
+                                && timer::state() != TimerState::NotRunning
+                            {
+                                completed_time = Some(stage_time);
+                            }
+                            BestKind::Stage
+                        }
+                        TimerMode::IL => {
+                            if player_hit_goal(&watchers)
+                                && timer::state() != TimerState::NotRunning
+                            {
+                                completed_time = Some(game_time.current());
+                            }
+                            BestKind::Level
+                        }
+                        TimerMode::Segment => BestKind::Segment {
+                            start: segment_start_checkpoint,
+                            end: segment_end_checkpoint,
+                        },
+                        TimerMode::TimeTrial => BestKind::TimeTrial {
+                            discount_bonus: settings.time_trial_discount_bonus,
+                        },
+                        TimerMode::TimeTrialMarathon => {
+                            BestKind::TimeTrial { discount_bonus: true }
+                        }
+                    };
+                    let best_key = BestKey { kind: best_kind, stage: level };
+                    if let Some(time) = completed_time {
+                        if let Some(previous) = bests.submit(best_key, time) {
+                            log_new_best(best_key, time, previous);
+                        }
+                    }
+                    update_best_variables(
+                        &mut bests,
+                        best_key,
+                        match best_kind {
+                            BestKind::Stage => stage_time,
+                            BestKind::Level | BestKind::Segment { .. } => game_time.current(),
+                            BestKind::TimeTrial { discount_bonus: true } => {
+                                time_trial_igt_pair.current - time_trial_bonus_pair.current as f64
+                            }
+                            BestKind::TimeTrial { discount_bonus: false } => {
+                                time_trial_igt_pair.current
+                            }
+                        },
+                    );
+
+                    if settings.debug_trace {
+                        trace_recorder.record(trace_frame(&watchers, load_detected), tick_time);
+                    }
+
+                    let new_tick_rate = match settings.tick_rate {
+                        TickRatePolicy::Adaptive => {
+                            adaptive_tick_rate(
+                                &watchers,
+                                &settings,
+                                load_detected,
+                                level_intro.is_some(),
+                            )
+                        }
+                        TickRatePolicy::High => HIGH_TICK_RATE,
+                        TickRatePolicy::Normal => NORMAL_TICK_RATE,
+                    };
+                    if new_tick_rate != tick_rate {
+                        tick_rate = new_tick_rate;
+                        asr::set_tick_rate(tick_rate);
+                    }
+                    timer::set_variable_int("Tick Rate", tick_rate as u32);
+
                     next_tick().await;
                 }
             })
@@ -325,6 +935,17 @@ async fn main() {
     }
 }
 
+#[derive(Gui, Clone, Copy, PartialEq)]
+pub enum TickRatePolicy {
+    /// Adaptive
+    #[default]
+    Adaptive,
+    /// Always 120 Hz
+    High,
+    /// Always 60 Hz
+    Normal,
+}
+
 #[derive(Gui, Clone, Copy, PartialEq)]
 pub enum TimerMode {
     /// Full Game
@@ -334,20 +955,150 @@ pub enum TimerMode {
     IL,
     /// Individual Level Series
     ILSeries,
+    /// Checkpoint Segment
+    Segment,
     /// Time Trial
     TimeTrial,
     /// Time Trial Marathon
     TimeTrialMarathon,
 }
 
+#[derive(Gui, Clone, Copy, PartialEq)]
+pub enum BossHpSplit {
+    /// Off
+    #[default]
+    Off,
+    /// Every Hit
+    EveryHit,
+    /// Every 25%
+    Quarters,
+    /// Every 50%
+    Halves,
+}
+
+#[derive(Gui, Clone, Copy, PartialEq)]
+pub enum ChallengeMode {
+    /// Off
+    #[default]
+    Off,
+    /// Hitless
+    Hitless,
+    /// Deathless
+    Deathless,
+}
+
+impl ChallengeMode {
+    fn to_string(self) -> &'static str {
+        match self {
+            ChallengeMode::Off => "Off",
+            ChallengeMode::Hitless => "Hitless",
+            ChallengeMode::Deathless => "Deathless",
+        }
+    }
+}
+
+#[derive(Gui, Clone, Copy, PartialEq)]
+pub enum ChallengeFailAction {
+    /// Reset the Timer
+    #[default]
+    Reset,
+    /// Mark the Run as Failed
+    MarkFailed,
+}
+
+#[derive(Gui, Clone, Copy, PartialEq)]
+pub enum FullGameTiming {
+    /// Load Removed
+    #[default]
+    LoadRemoved,
+    /// In-Game Time (Stage Clocks)
+    InGameTime,
+}
+
+#[derive(Gui, Clone, Copy, PartialEq)]
+pub enum SplitTimingPoint {
+    /// Return to Stage Select
+    #[default]
+    StageSelect,
+    /// Enter the Next Level
+    LevelEntry,
+    /// Goal Touch
+    GoalTouch,
+    /// Stage Goal
+    StageGoal,
+    /// Stage Exit
+    StageExit,
+}
+
+impl SplitTimingPoint {
+    fn level_end_point(self) -> Option<LevelEndPoint> {
+        match self {
+            SplitTimingPoint::StageSelect | SplitTimingPoint::LevelEntry => None,
+            SplitTimingPoint::GoalTouch => Some(LevelEndPoint::GoalTouch),
+            SplitTimingPoint::StageGoal => Some(LevelEndPoint::StageGoal),
+            SplitTimingPoint::StageExit => Some(LevelEndPoint::StageExit),
+        }
+    }
+}
+
+#[derive(Gui, Clone, Copy, PartialEq)]
+pub enum LevelEndPoint {
+    /// Goal Touch
+    #[default]
+    GoalTouch,
+    /// Stage Goal
+    StageGoal,
+    /// Stage Exit
+    StageExit,
+}
+
+#[derive(Gui, Clone, Copy, PartialEq)]
+pub enum ResetGuardSplits {
+    /// Never
+    #[default]
+    Off,
+    /// After 1 Split
+    After1,
+    /// After 3 Splits
+    After3,
+    /// After 5 Splits
+    After5,
+    /// After 10 Splits
+    After10,
+}
+
+#[derive(Gui, Clone, Copy, PartialEq)]
+pub enum ResetGuardTime {
+    /// Never
+    #[default]
+    Off,
+    /// After 5 Minutes
+    After5,
+    /// After 15 Minutes
+    After15,
+    /// After 30 Minutes
+    After30,
+    /// After 1 Hour
+    After60,
+}
+
 #[derive(Gui)]
 struct Settings {
     /// LiveSplit Timer Mode
     _timer_mode: Title,
 
     /// Pick a Mode
+    ///
+    /// Checkpoint Segment times from the checkpoint the player respawns at or passes to the next one or the goal,
+    /// every respawn starts it over.
     timer_mode: Pair<TimerMode>,
 
+    /// Full Game Timing Method
+    ///
+    /// In-Game Time adds up the stage clock of every stage played, including restarts.
+    /// The stage clock field is not confirmed yet, compare with "Load-removed vs In-Game Time" before using it.
+    full_game_timing: FullGameTiming,
+
     /// Start Options
     _title_start: Title,
 
@@ -370,6 +1121,22 @@ struct Settings {
     #[default = true]
     split_on_past_level_complete: bool,
 
+    /// Level Exit Only On First Clear
+    ///
+    /// Uses the save file to ignore levels that were already cleared, so backtracking never splits twice.
+    /// Off by default: which save flag belongs to which level is not confirmed yet, and a wrong
+    /// match would skip the split of a level that is cleared for the first time.
+    #[default = false]
+    split_first_clear_only: bool,
+
+    /// Full Game Level Split Timing
+    ///
+    /// Goal Touch is when Pac-Man reaches the goal, Stage Goal and Stage Exit are the stage states that follow it.
+    full_game_split_point: SplitTimingPoint,
+
+    /// Individual Level Series Split Timing
+    il_series_split_point: LevelEndPoint,
+
     /// Spooky Defeat
     #[default = true]
     split_spooky_qte: bool,
@@ -382,12 +1149,24 @@ struct Settings {
     #[default = true]
     split_il: bool,
 
+    /// Stage 100% Complete
+    ///
+    /// Splits the first time every collectible in a stage is picked up during the run.
+    #[default = false]
+    split_stage_complete: bool,
+
     /// Individual Level Boss Phase
     /// 
     /// Not supported for Individual Level Series
     #[default = false]
     split_boss_phase: bool,
 
+    /// Individual Level Boss HP
+    ///
+    /// Splits when the boss health drops past each threshold, starting over on every new phase.
+    /// Not supported for Individual Level Series
+    split_boss_hp: BossHpSplit,
+
     /// Individual Level Checkpoints
     ///
     /// You will need the same number of splits before the final one and checkpoints.
@@ -403,13 +1182,81 @@ struct Settings {
     #[default = true]
     reset_on_file_creation: bool,
 
+    /// Block New File Reset After Splits
+    ///
+    /// Protects long runs from accidentally starting a new file, the reset is skipped and logged instead.
+    reset_guard_splits: ResetGuardSplits,
+
+    /// Block New File Reset After Game Time
+    reset_guard_time: ResetGuardTime,
+
     /// Individual Level Start
     #[default = true]
     reset_on_level_start: bool,
 
+    /// Game Over
+    ///
+    /// Resets Individual Level and Individual Level Series runs on a game over, for practice.
+    #[default = false]
+    reset_on_game_over: bool,
+
+    /// Load Removal Options
+    _title_load_removal: Title,
+
+    /// Remove Level Loads
+    #[default = true]
+    remove_level_loads: bool,
+
+    /// Remove Stage Select Loads
+    ///
+    /// Loads into the stage select, Pac-Village and the menus.
+    #[default = true]
+    remove_stage_select_loads: bool,
+
+    /// Remove Death and Checkpoint Reloads
+    #[default = true]
+    remove_reloads: bool,
+
+    /// Remove Restarts From the Pause Menu
+    #[default = true]
+    remove_pause_restarts: bool,
+
+    /// Remove Loads on Individual Levels
+    ///
+    /// Applies the load types above to Individual Level and Individual Level Series, Full Game uses them with the Load Removed timing.
+    #[default = false]
+    remove_loads_il: bool,
+
+    /// Remove Unskippable Cutscenes
+    ///
+    /// Pauses the game time during the in-level cut-ins, for leaderboards that time without cutscenes.
+    /// The movies can be skipped so they are always timed.
+    #[default = false]
+    remove_unskippable_cutscenes: bool,
+
+    /// Challenge Options
+    _title_challenge: Title,
+
+    /// Challenge Run
+    ///
+    /// Hitless fails on any damage or death, Deathless only on a death.
+    challenge: ChallengeMode,
+
+    /// When the Challenge Fails
+    ///
+    /// Marking the run as failed keeps the timer going and shows the stage in the "Challenge Failed On" variable.
+    challenge_fail_action: ChallengeFailAction,
+
     /// Misc
     _misc_title: Title,
 
+    /// Remove Pause Time on Individual Levels
+    ///
+    /// Game time stops while the pause menu is open. The reload after restarting from the pause menu follows the load removal options.
+    /// Also used by Checkpoint Segment.
+    #[default = false]
+    remove_pause_time_il: bool,
+
     /// Discount Bonus Time on Time Trials
     #[default = true]
     time_trial_discount_bonus: bool,
@@ -419,6 +1266,25 @@ struct Settings {
     /// This way the delta column and sum of best will be more consistent
     #[default = true]
     time_trial_skip_negative: bool,
+
+    /// Tick Rate
+    ///
+    /// Adaptive runs at 120 Hz during loads, goals, time trials and on the title screen, 60 Hz in levels
+    /// and 20 Hz on stage select and the other menus while no run is going.
+    tick_rate: TickRatePolicy,
+
+    /// Tick Cost Profiling
+    ///
+    /// Times every memory read and shows the averages as variables, spikes are logged.
+    /// Only works on a build with the profiling feature.
+    #[default = false]
+    profile_tick_cost: bool,
+
+    /// Debug Trace
+    ///
+    /// Logs a line every time the game or timer state changes, the trace_timeline tool turns them into a readable timeline.
+    #[default = false]
+    debug_trace: bool,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Default)]
@@ -489,8 +1355,19 @@ struct Watchers {
     time_trial_bonus_time: Watcher<u32>,
     spooky_qte_success: Watcher<bool>,
     boss_state: Watcher<u32>,
+    boss_hp: Watcher<i32>,
+    boss_max_hp: Watcher<i32>,
     player_state: Watcher<PlayerState>,
     stage_state: Watcher<StageState>,
+    stage_time: Watcher<f32>,
+    health: Watcher<i32>,
+    lives: Watcher<i32>,
+    pac_dots: Watcher<i32>,
+    fruit: Watcher<i32>,
+    tokens: Watcher<i32>,
+    keys: Watcher<i32>,
+    stage_complete: Watcher<bool>,
+    progress: Watcher<progress::Progress>,
 }
 
 fn start(watchers: &Watchers, settings: &Settings) -> bool {
@@ -509,22 +1386,92 @@ fn start(watchers: &Watchers, settings: &Settings) -> bool {
         && settings.start_new_game
 }
 
+fn reset_guard_active(settings: &Settings, run_game_time: f64) -> bool {
+    if timer::state() != TimerState::Running && timer::state() != TimerState::Paused {
+        return false;
+    }
+
+    let split_limit = match settings.reset_guard_splits {
+        ResetGuardSplits::Off => None,
+        ResetGuardSplits::After1 => Some(1),
+        ResetGuardSplits::After3 => Some(3),
+        ResetGuardSplits::After5 => Some(5),
+        ResetGuardSplits::After10 => Some(10),
+    };
+    let minutes_limit = match settings.reset_guard_time {
+        ResetGuardTime::Off => None,
+        ResetGuardTime::After5 => Some(5.),
+        ResetGuardTime::After15 => Some(15.),
+        ResetGuardTime::After30 => Some(30.),
+        ResetGuardTime::After60 => Some(60.),
+    };
+
+    let splits_done = timer::current_split_index().unwrap_or_default();
+    split_limit.is_some_and(|limit| splits_done >= limit)
+        || minutes_limit.is_some_and(|limit| run_game_time >= limit * 60.)
+}
+
 fn split_full_game(watchers: &Watchers, settings: &Settings, level_split_enabled: bool) -> bool {
     // level exit split
     let level_pair = watchers.level_id.pair.unwrap_or_default();
 
-    if level_pair.changed() && level_split_enabled {
-        match level_pair.current {
-            GameStage::StageSelect => return settings.split_on_level_complete,
-            GameStage::StageSelectPast => return settings.split_on_past_level_complete,
-            GameStage::StageSelectSonic => return settings.split_on_level_complete,
-            _ => return false,
+    if level_split_enabled {
+        let split_point_reached = match settings.full_game_split_point {
+            SplitTimingPoint::StageSelect => {
+                level_pair.changed() && level_pair.current.is_stage_select()
+            }
+            SplitTimingPoint::LevelEntry => {
+                level_pair.changed()
+                    && level_pair.old.is_stage_select()
+                    && !level_pair.current.is_stage_select()
+            }
+            point => {
+                // pac-village has no goal, leaving it is the level end
+                let left_pac_village = level_pair.changed()
+                    && level_pair.old == GameStage::PacVillage
+                    && level_pair.current.is_stage_select();
+                left_pac_village
+                    || point
+                        .level_end_point()
+                        .is_some_and(|end_point| level_end_reached(watchers, end_point))
+            }
+        };
+
+        if split_point_reached {
+            let past_level = match settings.full_game_split_point {
+                SplitTimingPoint::StageSelect => level_pair.current == GameStage::StageSelectPast,
+                SplitTimingPoint::LevelEntry => level_pair.old == GameStage::StageSelectPast,
+                _ => {
+                    level_pair.current.is_past_stage()
+                        || level_pair.current == GameStage::StageSelectPast
+                }
+            };
+            return match past_level {
+                true => settings.split_on_past_level_complete,
+                false => settings.split_on_level_complete,
+            };
         }
     };
 
     split_final_boss(watchers, settings)
 }
 
+fn level_end_reached(watchers: &Watchers, point: LevelEndPoint) -> bool {
+    let stage_state_pair = watchers.stage_state.pair.unwrap_or_default();
+    match point {
+        LevelEndPoint::GoalTouch => player_hit_goal(watchers),
+        LevelEndPoint::StageGoal => stage_hit_goal(watchers),
+        LevelEndPoint::StageExit => {
+            stage_state_pair.changed() && stage_state_pair.current == StageState::Exit
+        }
+    }
+}
+
+fn stage_hit_goal(watchers: &Watchers) -> bool {
+    let stage_state_pair = watchers.stage_state.pair.unwrap_or_default();
+    stage_state_pair.changed() && stage_state_pair.current == StageState::Goal
+}
+
 fn split_final_boss(watchers: &Watchers, settings: &Settings) -> bool {
     // spooky qte final split
     if beat_spooky(watchers) && settings.split_spooky_qte {
@@ -548,12 +1495,11 @@ fn beat_toc_man(watchers: &Watchers) -> bool {
         && level_pair.current == GameStage::Stage6_5;
 }
 
-fn enable_reset_il(watchers: &Watchers) -> bool {
+fn enable_reset_il(watchers: &Watchers, intro_ended: bool) -> bool {
     // 3 cases that enable timer start:
     // * restart from menu while player is not dead and checkpoint is -1 (works on stage start before checkpoints)
     // * checkpoint returns to -1 while stage state is "pac dead"
-    // * start from level select
-    // TODO fix submarine levels
+    // * start from level select, after any of the stage intros
     let player_state_pair = watchers.player_state.pair.unwrap_or_default();
     let stage_state_pair = watchers.stage_state.pair.unwrap_or_default();
     let checkpoint_pair = watchers.checkpoint.pair.unwrap_or_default();
@@ -564,23 +1510,80 @@ fn enable_reset_il(watchers: &Watchers) -> bool {
         || (checkpoint_pair.changed()
             && checkpoint_pair.current == -1
             && stage_state_pair.current == StageState::PacDead)
-        || (player_state_pair.old == PlayerState::StageInit
-            && (player_state_pair.current == PlayerState::Control
-                || player_state_pair.current == PlayerState::Shooting));
+        || intro_ended;
+}
+
+// every stage type has its own intro and control states:
+// * on foot: StageInit (or StageInitMaze) -> Control
+// * space jump: StageInitSJ -> SpaceJump -> SpaceJumpOut -> Control
+// * submarine/shooting: StageInit -> Shooting
+// * racing: StageInit -> Racing
+// the level is always entered with one of the init states
+fn player_state_is_intro(state: PlayerState) -> bool {
+    state == PlayerState::StageInit
+        || state == PlayerState::StageInitMaze
+        || state == PlayerState::StageInitSJ
+}
+
+// the space jump states are only part of the intro when the level was entered with StageInitSJ,
+// the same jumps happen in the middle of a level
+fn intro_continues(intro: PlayerState, state: PlayerState) -> bool {
+    match intro {
+        PlayerState::StageInitSJ => matches!(
+            state,
+            PlayerState::StageInitSJ | PlayerState::SpaceJump | PlayerState::SpaceJumpOut
+        ),
+        intro => state == intro,
+    }
+}
+
+// follows the intro the level was entered with, true when it ends with the player in control
+fn level_intro_ended(level_intro: &mut Option<PlayerState>, watchers: &Watchers) -> bool {
+    let player_state_pair = watchers.player_state.pair.unwrap_or_default();
+    if !player_state_pair.changed() {
+        return false;
+    }
+    if player_state_is_intro(player_state_pair.current) {
+        *level_intro = Some(player_state_pair.current);
+        return false;
+    }
+    match *level_intro {
+        Some(intro) if intro_continues(intro, player_state_pair.current) => false,
+        Some(_) => {
+            *level_intro = None;
+            player_state_has_control(player_state_pair.current)
+        }
+        None => false,
+    }
+}
+
+fn player_state_has_control(state: PlayerState) -> bool {
+    state == PlayerState::Control || player_state_is_vehicle(state)
+}
+
+fn player_state_is_vehicle(state: PlayerState) -> bool {
+    state == PlayerState::Shooting || state == PlayerState::Racing
 }
 
 fn player_gained_control(watchers: &Watchers) -> bool {
     let player_state_pair = watchers.player_state.pair.unwrap_or_default();
-    return (player_state_pair.old != PlayerState::Control
-        && player_state_pair.current == PlayerState::Control)
-        || player_state_pair.old != PlayerState::Shooting
-            && player_state_pair.current == PlayerState::Shooting;
+    player_state_pair.changed()
+        && !player_state_has_control(player_state_pair.old)
+        && player_state_has_control(player_state_pair.current)
 }
 
 fn player_hit_goal(watchers: &Watchers) -> bool {
     let player_state_pair = watchers.player_state.pair.unwrap_or_default();
-    return player_state_pair.current != player_state_pair.old
-        && player_state_pair.current == PlayerState::Goal;
+    // vehicles don't switch to the goal state, the stage does
+    (player_state_pair.current != player_state_pair.old
+        && player_state_pair.current == PlayerState::Goal)
+        || (player_state_is_vehicle(player_state_pair.current) && stage_hit_goal(watchers))
+}
+
+fn entered_level_from_stage_select(level_pair: Pair<GameStage>) -> bool {
+    level_pair.changed()
+        && level_pair.old.is_stage_select()
+        && !level_pair.current.is_stage_select()
 }
 
 fn enable_full_game_level_splits(watchers: &Watchers) -> bool {
@@ -589,6 +1592,7 @@ fn enable_full_game_level_splits(watchers: &Watchers) -> bool {
 
     (player_state_pair.current == PlayerState::Goal
         && player_state_pair.current != player_state_pair.old)
+        || stage_hit_goal(watchers)
         || ((stage_pair.current == GameStage::StageSelect
             || stage_pair.current == GameStage::StageSelectPast)
             && stage_pair.old == GameStage::PacVillage)
@@ -631,25 +1635,212 @@ fn split_boss_phase(
     false
 }
 
-fn level_is_stage_select(stage: GameStage) -> bool {
-    stage == GameStage::StageSelect
-        || stage == GameStage::StageSelectPast
-        || stage == GameStage::StageSelectSonic
-}
-
-fn level_is_boss_stage(stage: GameStage) -> bool {
-    stage == GameStage::Stage1_4
-        || stage == GameStage::Stage2_4
-        || stage == GameStage::Stage3_4
-        || stage == GameStage::Stage4_4
-        || stage == GameStage::Stage5_4
-        || stage == GameStage::Stage6_4
-        || stage == GameStage::Stage6_5
-        || stage == GameStage::Stage1_4Past
-        || stage == GameStage::Stage2_4Past
-        || stage == GameStage::Stage3_4Past
-        || stage == GameStage::Stage4_4Past
-        || stage == GameStage::Stage5_4Past
-        || stage == GameStage::Stage6_4Past
-        || stage == GameStage::StageSonic3
+fn split_boss_hp(watchers: &Watchers, settings: &Settings, lowest_segment: &mut u32) -> bool {
+    let boss_phase_pair = watchers.boss_state.pair.unwrap_or_default();
+    let boss_hp_pair = watchers.boss_hp.pair.unwrap_or_default();
+    let boss_max_hp = watchers.boss_max_hp.pair.unwrap_or_default().current;
+
+    // health can refill between phases, so the thresholds start over
+    if boss_phase_pair.changed() {
+        *lowest_segment = u32::MAX;
+    }
+
+    // the killing blow is already covered by the phase and level end splits
+    if !boss_hp_pair.decreased() || boss_hp_pair.current <= 0 || boss_max_hp <= 0 {
+        return false;
+    }
+
+    let segments = match settings.split_boss_hp {
+        BossHpSplit::Off => return false,
+        BossHpSplit::EveryHit => return true,
+        BossHpSplit::Quarters => 4,
+        BossHpSplit::Halves => 2,
+    };
+
+    // how many of the threshold segments still have health left in them
+    let remaining_segments =
+        |hp: i32| ((hp.min(boss_max_hp) * segments + boss_max_hp - 1) / boss_max_hp) as u32;
+    let current_segment = remaining_segments(boss_hp_pair.current);
+
+    if current_segment < remaining_segments(boss_hp_pair.old) && current_segment < *lowest_segment
+    {
+        *lowest_segment = current_segment;
+        return true;
+    }
+    false
+}
+
+fn log_new_best(key: BestKey, time: f64, previous: Option<f64>) {
+    let kind = match key.kind {
+        BestKind::Stage => "stage",
+        BestKind::Level => "level",
+        BestKind::Segment { .. } => "segment",
+        BestKind::TimeTrial { .. } => "time trial",
+    };
+    match previous {
+        Some(previous) => asr::print_limited::<128>(&format_args!(
+            "New {} best on {}: {:.3}s, {:.3}s faster",
+            kind,
+            key.stage.to_string(),
+            time,
+            previous - time
+        )),
+        None => asr::print_limited::<128>(&format_args!(
+            "First {} best on {}: {:.3}s",
+            kind,
+            key.stage.to_string(),
+            time
+        )),
+    }
+}
+
+// only the levels have bests, the variables keep the last level's values on the stage select
+fn update_best_variables(bests: &mut Bests, key: BestKey, current_time: f64) {
+    if matches!(key.stage.kind(), StageKind::Hub | StageKind::StageSelect | StageKind::Menu) {
+        return;
+    }
+    match bests.best(key) {
+        Some(best) => {
+            timer::set_variable_float("Stage Best", best);
+            timer::set_variable_float("Delta vs Stage Best", current_time - best);
+        }
+        None => {
+            timer::set_variable("Stage Best", "None");
+            timer::set_variable("Delta vs Stage Best", "None");
+        }
+    }
+}
+
+fn challenge_fails(challenge: ChallengeMode, event: DamageEvent) -> bool {
+    match challenge {
+        ChallengeMode::Off => false,
+        ChallengeMode::Hitless => true,
+        ChallengeMode::Deathless => event == DamageEvent::Death,
+    }
+}
+
+// gives the stage the game over happened on, the game over scene follows the stage state so either is enough
+fn game_over_started(watchers: &Watchers, in_game_over: &mut bool) -> Option<GameStage> {
+    let level_pair = watchers.level_id.pair.unwrap_or_default();
+    let stage_state_pair = watchers.stage_state.pair.unwrap_or_default();
+
+    if *in_game_over {
+        if player_gained_control(watchers) {
+            *in_game_over = false;
+        }
+        return None;
+    }
+
+    let stage = if stage_state_pair.changed() && stage_state_pair.current == StageState::GameOver
+    {
+        level_pair.current
+    } else if level_pair.changed() && level_pair.current == GameStage::GameOver {
+        level_pair.old
+    } else {
+        return None;
+    };
+    *in_game_over = true;
+    Some(stage)
+}
+
+// leaving the pause menu always resumes, a restart from it reloads the level and that reload is
+// removed or kept by the load removal settings
+fn remove_pause_time(watchers: &Watchers, game_time: &mut GameTime) {
+    let stage_state_pair = watchers.stage_state.pair.unwrap_or_default();
+    let paused = |state: StageState| state == StageState::Pause || state == StageState::DebugPause;
+
+    if stage_state_pair.changed() {
+        if paused(stage_state_pair.current) {
+            game_time.pause();
+        } else if paused(stage_state_pair.old) {
+            game_time.resume();
+        }
+    }
+}
+
+fn load_removed(settings: &Settings, kind: LoadKind) -> bool {
+    match kind {
+        LoadKind::Level => settings.remove_level_loads,
+        LoadKind::StageSelect => settings.remove_stage_select_loads,
+        LoadKind::Reload => settings.remove_reloads,
+        LoadKind::PauseRestart => settings.remove_pause_restarts,
+    }
+}
+
+// the game time can already be paused for something else when a load or cutscene ends, then it's left paused
+fn remove_time_il(
+    removing: bool,
+    removal_paused: &mut bool,
+    keep_paused: bool,
+    game_time: &mut GameTime,
+) {
+    if removing && !*removal_paused {
+        game_time.pause();
+        *removal_paused = true;
+    } else if !removing && *removal_paused {
+        if !keep_paused {
+            game_time.resume();
+        }
+        *removal_paused = false;
+    }
+}
+
+fn adaptive_tick_rate(
+    watchers: &Watchers,
+    settings: &Settings,
+    loading: bool,
+    in_level_intro: bool,
+) -> f64 {
+    let level_pair = watchers.level_id.pair.unwrap_or_default();
+    let player_state = watchers.player_state.pair.unwrap_or_default().current;
+    // the stage state isn't read on the stage select, it still holds the state the level ended on
+    let stage_state = match level_pair.current.is_stage_select() {
+        true => StageState::None,
+        false => watchers.stage_state.pair.unwrap_or_default().current,
+    };
+
+    // everything that starts, stops or splits the timer.
+    // title to movie is the full game start, the timer gets a fixed offset from the frame it's seen
+    if loading
+        || level_pair.changed()
+        || level_pair.current == GameStage::Title
+        || matches!(
+            settings.timer_mode.current,
+            TimerMode::TimeTrial | TimerMode::TimeTrialMarathon
+        )
+        || in_level_intro
+        || matches!(player_state, PlayerState::Goal | PlayerState::StageEnd)
+        || matches!(
+            stage_state,
+            StageState::InitOnFade
+                | StageState::InitEndFade
+                | StageState::PacDead
+                | StageState::Goal
+                | StageState::Exit
+        )
+    {
+        return HIGH_TICK_RATE;
+    }
+
+    let idle_scene =
+        level_pair.current.is_stage_select() || level_pair.current.kind() == StageKind::Menu;
+    match idle_scene && timer::state() != TimerState::Running {
+        true => LOW_TICK_RATE,
+        false => NORMAL_TICK_RATE,
+    }
+}
+
+fn trace_frame(watchers: &Watchers, loading: bool) -> TraceFrame {
+    TraceFrame {
+        level: watchers.level_id.pair.unwrap_or_default().current.to_string(),
+        player_state: player_state_to_string(watchers.player_state.pair.unwrap_or_default().current),
+        stage_state: stage_state_to_string(watchers.stage_state.pair.unwrap_or_default().current),
+        time_trial_state: time_trial_state_to_string(
+            watchers.time_trial_state.pair.unwrap_or_default().current,
+        ),
+        checkpoint: watchers.checkpoint.pair.unwrap_or_default().current,
+        loading,
+        timer_state: timer::state(),
+        split_index: timer::current_split_index(),
+    }
 }
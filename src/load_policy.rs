@@ -0,0 +1,65 @@
+use asr::watcher::Pair;
+
+use crate::{
+    stages::{GameStage, StageKind},
+    StageState,
+};
+
+// what a load is for, leaderboards don't all remove the same ones
+#[derive(Clone, Copy, PartialEq)]
+pub enum LoadKind {
+    Level,
+    StageSelect,
+    Reload,
+    PauseRestart,
+}
+
+impl LoadKind {
+    pub fn to_string(self) -> &'static str {
+        match self {
+            LoadKind::Level => "Level",
+            LoadKind::StageSelect => "Stage Select",
+            LoadKind::Reload => "Death or Checkpoint Reload",
+            LoadKind::PauseRestart => "Restart From Pause",
+        }
+    }
+}
+
+// the stage state before a load tells reloads apart, otherwise it's the level the load goes to
+#[derive(Default)]
+pub struct LoadClassifier {
+    // the stage is reloading, until the player is back in control
+    reload: Option<LoadKind>,
+}
+
+impl LoadClassifier {
+    pub fn update(
+        &mut self,
+        loading: bool,
+        level_pair: Pair<GameStage>,
+        stage_state_pair: Pair<StageState>,
+        has_control: bool,
+    ) -> Option<LoadKind> {
+        if stage_state_pair.changed() && stage_state_pair.current == StageState::PacDead {
+            self.reload = match stage_state_pair.old {
+                StageState::Pause | StageState::DebugPause => Some(LoadKind::PauseRestart),
+                _ => Some(LoadKind::Reload),
+            };
+        }
+        // leaving the level during the reload makes it a level load
+        if level_pair.changed() || (has_control && !loading) {
+            self.reload = None;
+        }
+
+        if !loading {
+            return None;
+        }
+        let level = level_pair.current;
+        Some(self.reload.unwrap_or(
+            match level.is_stage_select() || level.kind() == StageKind::Menu {
+                true => LoadKind::StageSelect,
+                false => LoadKind::Level,
+            },
+        ))
+    }
+}
@@ -0,0 +1,205 @@
+// Decides if a tick is part of a load, giving the same removed time at any frame rate.
+//
+// A load is `SceneManager.m_bProcessing` followed by the loading bar animation (`LoadingUIBase.m_fProgPrev`
+// going from 0 to 1). The animation is timed by the game, but the bar only moves once per frame:
+// at a low frame rate it starts a frame after the processing and reaches 1 up to a frame late,
+// so reading the flags as they are removes more time the lower the frame rate is.
+// Instead the animation is taken to start the moment the processing ends, and its end is predicted from
+// how fast the bar moved so far, which doesn't depend on the frame rate.
+//
+// Kept free of asr so the tests can run it on synthetic loads at different frame rates.
+
+// if the bar doesn't start moving after the processing it wasn't a load with the animation
+const ANIMATION_START_TIMEOUT: f64 = 0.25;
+
+#[derive(Clone, Copy, Default)]
+enum Phase {
+    #[default]
+    Idle,
+    // the bar is reset to 0 by loads that show the animation afterwards
+    Processing {
+        bar_reset: bool,
+    },
+    // `elapsed` is the time since the processing ended, `progress_time` when the bar got to `progress`
+    Animation {
+        elapsed: f64,
+        progress: f32,
+        progress_time: f64,
+    },
+}
+
+#[derive(Default)]
+pub struct LoadDetector {
+    phase: Phase,
+}
+
+impl LoadDetector {
+    // `tick_time` is the time since the previous tick
+    pub fn update(&mut self, processing: bool, progress: f32, tick_time: f64) -> bool {
+        if processing {
+            let bar_reset =
+                progress < 1. || matches!(self.phase, Phase::Processing { bar_reset: true });
+            self.phase = Phase::Processing { bar_reset };
+            return true;
+        }
+
+        self.phase = match self.phase {
+            Phase::Idle | Phase::Processing { bar_reset: false } => Phase::Idle,
+            Phase::Processing { bar_reset: true } => Phase::Animation {
+                elapsed: 0.,
+                progress: 0.,
+                progress_time: 0.,
+            },
+            Phase::Animation {
+                elapsed,
+                progress: last_progress,
+                progress_time,
+            } => {
+                let elapsed = elapsed + tick_time;
+                match progress > last_progress {
+                    true => Phase::Animation {
+                        elapsed,
+                        progress,
+                        progress_time: elapsed,
+                    },
+                    false => Phase::Animation {
+                        elapsed,
+                        progress: last_progress,
+                        progress_time,
+                    },
+                }
+            }
+        };
+
+        match self.phase {
+            // the animation without the processing, same as it was always read
+            Phase::Idle => progress > 0. && progress < 1.,
+            Phase::Processing { .. } => true,
+            Phase::Animation {
+                elapsed,
+                progress,
+                progress_time,
+            } => {
+                let finished = match progress > 0. {
+                    true => progress >= 1. || elapsed >= progress_time / progress as f64,
+                    false => elapsed >= ANIMATION_START_TIMEOUT,
+                };
+                if finished {
+                    self.phase = Phase::Idle;
+                }
+                !finished
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::LoadDetector;
+
+    // the tick rate while loading
+    const TICK_RATE: f64 = 120.;
+    // one tick either way, the flags are only seen when a tick happens
+    const TOLERANCE: f64 = 1. / TICK_RATE;
+
+    struct Load {
+        start: f64,
+        processing: f64,
+        // the bar animation after the processing, None for a load without it
+        animation: Option<f64>,
+    }
+
+    // m_bProcessing and m_fProgPrev of the frame shown at `time`, the flags only change on a frame
+    fn sample(load: &Load, frame_rate: f64, time: f64) -> (bool, f32) {
+        let frame_time = 1. / frame_rate;
+        let frame_start = (time / frame_time).floor() * frame_time;
+        // the game notices the start and end of the processing on a frame
+        let processing_start = (load.start / frame_time).ceil() * frame_time;
+        let processing_end = ((load.start + load.processing) / frame_time).ceil() * frame_time;
+
+        if frame_start < processing_start {
+            return (false, 1.);
+        }
+        if frame_start < processing_end {
+            return (true, 0.);
+        }
+        match load.animation {
+            // the bar moves by the time of a frame on every frame after the processing
+            Some(animation) => {
+                let frames = ((frame_start - processing_end) / frame_time).round();
+                (false, (frames * frame_time / animation).min(1.) as f32)
+            }
+            None => (false, 1.),
+        }
+    }
+
+    fn removed_time(load: &Load, frame_rate: f64) -> f64 {
+        let tick_time = 1. / TICK_RATE;
+        let end = load.start + load.processing + load.animation.unwrap_or_default() + 1.;
+        let mut detector = LoadDetector::default();
+        let mut removed = 0.;
+
+        let mut tick = 0;
+        loop {
+            let time = tick as f64 * tick_time;
+            if time > end {
+                break;
+            }
+            let (processing, progress) = sample(load, frame_rate, time);
+            if detector.update(processing, progress, tick_time) {
+                removed += tick_time;
+            }
+            tick += 1;
+        }
+        removed
+    }
+
+    fn assert_same_at_every_frame_rate(load: Load) {
+        let expected = load.processing + load.animation.unwrap_or_default();
+        for frame_rate in [30., 60., 144.] {
+            let removed = removed_time(&load, frame_rate);
+            assert!(
+                (removed - expected).abs() <= TOLERANCE,
+                "{frame_rate} FPS removed {removed:.3}s instead of {expected:.3}s"
+            );
+        }
+    }
+
+    #[test]
+    fn level_load() {
+        assert_same_at_every_frame_rate(Load {
+            start: 0.5,
+            processing: 2.,
+            animation: Some(0.75),
+        });
+    }
+
+    #[test]
+    fn short_load() {
+        assert_same_at_every_frame_rate(Load {
+            start: 0.3,
+            processing: 0.4,
+            animation: Some(0.75),
+        });
+    }
+
+    #[test]
+    fn load_off_the_frame() {
+        assert_same_at_every_frame_rate(Load {
+            start: 0.4567,
+            processing: 1.2345,
+            animation: Some(0.6),
+        });
+    }
+
+    #[test]
+    fn load_without_the_bar() {
+        assert_same_at_every_frame_rate(Load {
+            start: 0.5,
+            processing: 1.,
+            animation: None,
+        });
+    }
+}
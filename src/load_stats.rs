@@ -0,0 +1,78 @@
+use crate::{
+    stages::{GameStage, STAGE_SAVE_ORDER},
+    totals::Totals,
+};
+
+// the last slot is for the loads into pac-village, stage select and the menus
+const LEVEL_SLOTS: usize = STAGE_SAVE_ORDER.len() + 1;
+
+// load time removed from a run, for run verification
+// a load belongs to the level it ends in, that's the level that was being loaded.
+// loads into a maze or bonus stage count for the level it's part of
+#[derive(Default)]
+pub struct LoadStats {
+    total: f64,
+    count: u32,
+    longest: f64,
+    current_load: f64,
+    level_totals: Totals<LEVEL_SLOTS>,
+}
+
+impl LoadStats {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    // `level` is the parent level of the scene, None outside of the levels
+    pub fn update(&mut self, loading: bool, level: Option<GameStage>, tick_time: f64) {
+        if loading {
+            self.current_load += tick_time;
+            self.total += tick_time;
+        } else if self.current_load > 0. {
+            self.count += 1;
+            self.longest = self.longest.max(self.current_load);
+            self.level_totals[level_slot(level)] += self.current_load;
+            self.current_load = 0.;
+        }
+    }
+
+    pub fn total(&self) -> f64 {
+        self.total
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    pub fn longest(&self) -> f64 {
+        self.longest.max(self.current_load)
+    }
+
+    pub fn level_total(&self, level: Option<GameStage>) -> f64 {
+        self.level_totals[level_slot(level)]
+    }
+
+    pub fn print_summary(&self) {
+        asr::print_limited::<128>(&format_args!(
+            "Loads removed: {:.3}s over {} loads, longest {:.3}s",
+            self.total,
+            self.count,
+            self.longest()
+        ));
+        for (stage, total) in STAGE_SAVE_ORDER.iter().zip(self.level_totals.iter()) {
+            if *total > 0. {
+                asr::print_limited::<128>(&format_args!("  {}: {:.3}s", stage.to_string(), total));
+            }
+        }
+        let other = self.level_totals[LEVEL_SLOTS - 1];
+        if other > 0. {
+            asr::print_limited::<128>(&format_args!("  Menus and stage select: {other:.3}s"));
+        }
+    }
+}
+
+fn level_slot(level: Option<GameStage>) -> usize {
+    level
+        .and_then(GameStage::save_index)
+        .unwrap_or(LEVEL_SLOTS - 1)
+}
@@ -1,76 +1,76 @@
 use crate::{
-    level_is_boss_stage, level_is_stage_select, stages::GameStage, PlayerState, Settings,
-    StageState, TimeTrialState, TimerMode, Watchers,
-};
-use asr::{
-    game_engine::unity::il2cpp::{Image, Module, UnityPointer, Version},
-    print_message, Process,
+    collectibles::StageCollectibles,
+    game::GameMemory,
+    profiler::{Profiler, Section, SECTIONS},
+    progress::Progress,
+    singleton::{Field, Singleton},
+    stages::GameStage,
+    PlayerState, Settings, StageState, TimeTrialState, TimerMode, Watchers,
 };
+use asr::{game_engine::unity::il2cpp::UnityPointer, print_message};
 
 pub struct Memory {
-    il2cpp_module: Module,
-    game_assembly: Image,
-    is_loading: UnityPointer<2>,
+    // the managers read every tick are cached instead of walking the pointer path for every field,
+    // they are invalidated on scene changes and load ends so a recreated manager is picked up
+    scene_manager: Singleton,
+    current_scene: Field,
+    // is_loading
+    processing: Field,
+    stage_state_manager: Singleton,
+    check_point_priority: Field,
+    time_attack_manager: Singleton,
+    time_attack_time: Field,
+    time_attack_step: Field,
+    bonus_time_list: Field,
+    stage_manager: Singleton,
+    stage_step: Field,
+    // the stage clock. the field name is not confirmed from a dump of the game, if it doesn't exist
+    // the read fails and the in-game time timing stays at 0. the results screen time is not read
+    play_time: Field,
+    game_manager: Singleton,
     // is_loading is not fully accurate, there is an animation at the loading screen that depends on the frame rate and is_loading is set to false during that
-    // the solution found was using the an UI param to complement is_loading
+    // the solution found was using the an UI param to complement is_loading, load_removal.rs times it so every frame rate removes the same
     loadscreen_ui_pointer: UnityPointer<2>,
     load_progress_gui_offset: Option<u32>,
-    level_id: UnityPointer<2>,
-    checkpoint: UnityPointer<2>,
-    time_trial_igt: UnityPointer<2>,
-    time_trial_state: UnityPointer<2>,
-    time_trial_bonus_list_pointer: UnityPointer<2>,
     spooky_qte_success: UnityPointer<3>,
     // conviniently all bosses are inherited from "BossBase" and use "m_state" as their current phase
     // only detail is that their offsets in the pointer path are different so they are refreshed on a level change
     boss_state: UnityPointer<3>,
+    // same as the boss state, health is in the base class so it works with every boss
+    boss_hp: UnityPointer<3>,
+    boss_max_hp: UnityPointer<3>,
     players_array: UnityPointer<3>,
     player_state_offset: Option<u32>,
-    stage_manager_state: UnityPointer<3>,
+    // the stats are spread between the player object and the stage/game managers,
+    // so they are read from the manager instances with the class offsets
+    player_stats_offsets: PlayerStatsOffsets,
+    collectible_offsets: CollectibleOffsets,
+    save_data: UnityPointer<2>,
+    save_data_offsets: SaveDataOffsets,
+    profiler: Profiler,
+    profiling: bool,
+    profiling_unavailable_logged: bool,
     // WIP
     /* title_scene_step: UnityPointer<3>, */
 }
 
 impl Memory {
-    pub fn init(game: &Process) -> Option<Self> {
-        let il2cpp_module = Module::attach(game, Version::V2020)?;
-        let game_assembly = il2cpp_module.get_default_image(game)?;
-
-        let is_loading = UnityPointer::new("SceneManager", 1, &["s_sInstance", "m_bProcessing"]);
+    pub fn init(game: &impl GameMemory) -> Self {
         let loadscreen_ui_pointer =
             UnityPointer::new("SystemUIRoot", 1, &["s_sInstance", "m_sLoadingUI"]);
-        let level_id = UnityPointer::new("SceneManager", 1, &["s_sInstance", "m_eCurrentScene"]);
-        let checkpoint = UnityPointer::new(
-            "StageStateManager",
-            1,
-            &["s_sInstance", "m_checkPointPriority"],
-        );
-        let time_trial_igt = UnityPointer::new("TimeAttackManager", 1, &["s_sInstance", "m_time"]);
-        let time_trial_state =
-            UnityPointer::new("TimeAttackManager", 1, &["s_sInstance", "m_step"]);
-        let time_trial_bonus_list_pointer =
-            UnityPointer::new("TimeAttackManager", 1, &["s_sInstance", "m_bonusTimeList"]);
         let spooky_qte_success =
             UnityPointer::new("BossSpooky", 3, &["s_sInstance", "m_qteSuccess"]);
         let boss_state = UnityPointer::new("BossBase", 1, &["s_sInstance", "m_state"]);
+        let boss_hp = UnityPointer::new("BossBase", 1, &["s_sInstance", "m_hp"]);
+        let boss_max_hp = UnityPointer::new("BossBase", 1, &["s_sInstance", "m_hpMax"]);
         let players_array = UnityPointer::new("PlayerManager", 2, &["s_sInstance", "m_players"]);
-        let stage_manager_state = UnityPointer::new("StageManager", 2, &["s_sInstance", "m_step"]);
+        let save_data = UnityPointer::new("SaveDataManager", 1, &["s_sInstance", "m_saveData"]);
 
         // init the gui loading progress in the LoadingUIBase class
-        let loading_ui_class_opt = game_assembly.get_class(game, &il2cpp_module, "LoadingUIBase");
-        let load_progress_gui_offset = if let Some(load_ui_class) = loading_ui_class_opt {
-            load_ui_class.get_field_offset(game, &il2cpp_module, "m_fProgPrev")
-        } else {
-            None
-        };
+        let load_progress_gui_offset = game.field_offset("LoadingUIBase", "m_fProgPrev");
 
         // init the player state offset in the PlayerPacman class
-        let pacman_class_opt = game_assembly.get_class(game, &il2cpp_module, "PlayerPacman");
-        let player_state_offset = if let Some(player_class) = pacman_class_opt {
-            player_class.get_field_offset(game, &il2cpp_module, "m_step")
-        } else {
-            None
-        };
+        let player_state_offset = game.field_offset("PlayerPacman", "m_step");
 
         // TODO cope for a better autostart
         // GameLevelSelect seems to be the UI to pick difficulty but theres no reference to it on a field...
@@ -81,108 +81,259 @@ impl Memory {
         // 2nd best thing thats easy to use but might as well keep using the intro level video if its not frame perfect, this is like half a second off still
         /* let title_scene_step = UnityPointer::new("TitleScene", 2, &["s_sInstance", "m_step"]); */
 
-        Some(Self {
-            il2cpp_module,
-            game_assembly,
-            is_loading,
+        Self {
+            scene_manager: Singleton::new("SceneManager", 1),
+            current_scene: Field::new("m_eCurrentScene"),
+            processing: Field::new("m_bProcessing"),
+            stage_state_manager: Singleton::new("StageStateManager", 1),
+            check_point_priority: Field::new("m_checkPointPriority"),
+            time_attack_manager: Singleton::new("TimeAttackManager", 1),
+            time_attack_time: Field::new("m_time"),
+            time_attack_step: Field::new("m_step"),
+            bonus_time_list: Field::new("m_bonusTimeList"),
+            stage_manager: Singleton::new("StageManager", 2),
+            stage_step: Field::new("m_step"),
+            play_time: Field::new("m_playTime"),
+            game_manager: Singleton::new("GameManager", 1),
             loadscreen_ui_pointer,
             load_progress_gui_offset,
-            checkpoint,
-            level_id,
-            time_trial_igt,
-            time_trial_state,
-            time_trial_bonus_list_pointer,
             spooky_qte_success,
             boss_state,
+            boss_hp,
+            boss_max_hp,
             players_array,
             player_state_offset,
-            stage_manager_state,
+            player_stats_offsets: PlayerStatsOffsets::default(),
+            collectible_offsets: CollectibleOffsets::default(),
+            save_data,
+            save_data_offsets: SaveDataOffsets::default(),
+            profiler: Profiler::default(),
+            profiling: false,
+            profiling_unavailable_logged: false,
             /* title_scene_step, */
-        })
+        }
     }
 
     // UnityPointer::new is very slow! avoid using at the same time as other values may change, like loading
     pub fn refresh_boss_state_pointer_path(&mut self) {
         self.boss_state = UnityPointer::new("BossBase", 1, &["s_sInstance", "m_state"]);
+        self.boss_hp = UnityPointer::new("BossBase", 1, &["s_sInstance", "m_hp"]);
+        self.boss_max_hp = UnityPointer::new("BossBase", 1, &["s_sInstance", "m_hpMax"]);
         print_message("Boss state pointer path refreshed");
     }
     pub fn refresh_stage_manager_pointer_path(&mut self) {
-        self.stage_manager_state = UnityPointer::new("StageManager", 2, &["s_sInstance", "m_step"]);
+        self.stage_manager
+            .refresh(&mut [&mut self.stage_step, &mut self.play_time]);
         print_message("Stage manager pointer path refreshed");
     }
 
-    pub fn refresh_gui_load_prog_offset(&mut self, game: &Process) {
-        let loading_ui_class_opt =
-            self.game_assembly
-                .get_class(game, &self.il2cpp_module, "LoadingUIBase");
-        if let Some(gui) = loading_ui_class_opt {
-            let offset_opt = gui.get_field_offset(game, &self.il2cpp_module, "m_fProgPrev");
-            self.load_progress_gui_offset = offset_opt;
-        } else {
-            self.load_progress_gui_offset = None;
+    pub fn refresh_gui_load_prog_offset(&mut self, game: &impl GameMemory) {
+        self.load_progress_gui_offset = game.field_offset("LoadingUIBase", "m_fProgPrev");
+    }
+
+    pub fn refresh_player_state_offset(&mut self, game: &impl GameMemory) {
+        self.player_state_offset = game.field_offset("PlayerPacman", "m_step");
+    }
+
+    pub fn refresh_player_stats_offsets(&mut self, game: &impl GameMemory) {
+        self.player_stats_offsets = PlayerStatsOffsets {
+            health: game.field_offset("PlayerPacman", "m_hp"),
+            lives: game.field_offset("GameManager", "m_life"),
+            pac_dots: game.field_offset("StageManager", "m_pacDotCount"),
+            fruit: game.field_offset("StageManager", "m_fruitCount"),
+            tokens: game.field_offset("GameManager", "m_tokenCount"),
+            keys: game.field_offset("StageManager", "m_keyCount"),
+        };
+        print_message("Player stats offsets refreshed");
+    }
+
+    pub fn refresh_collectible_offsets(&mut self, game: &impl GameMemory) {
+        self.collectible_offsets = CollectibleOffsets {
+            pac_dots_total: game.field_offset("StageManager", "m_pacDotMax"),
+            fruit_total: game.field_offset("StageManager", "m_fruitMax"),
+            tokens: game.field_offset("StageManager", "m_tokenCount"),
+            tokens_total: game.field_offset("StageManager", "m_tokenMax"),
+            galaxian_pieces: game.field_offset("StageManager", "m_galaxianCount"),
+            galaxian_pieces_total: game.field_offset("StageManager", "m_galaxianMax"),
         };
+        print_message("Collectible offsets refreshed");
     }
 
-    pub fn refresh_player_state_offset(&mut self, game: &Process) {
-        let pacman_class_opt =
-            self.game_assembly
-                .get_class(game, &self.il2cpp_module, "PlayerPacman");
-        if let Some(pac) = pacman_class_opt {
-            let offset_opt = pac.get_field_offset(game, &self.il2cpp_module, "m_step");
-            self.player_state_offset = offset_opt;
-        } else {
-            self.player_state_offset = None;
+    pub fn refresh_save_data_offsets(&mut self, game: &impl GameMemory) {
+        self.save_data_offsets = SaveDataOffsets {
+            stage_clear: game.field_offset("SaveData", "m_stageClear"),
+            past_unlock: game.field_offset("SaveData", "m_pastUnlock"),
+            sonic_unlock: game.field_offset("SaveData", "m_sonicUnlock"),
+            museum_buy: game.field_offset("SaveData", "m_museumBuy"),
         };
+        print_message("Save data offsets refreshed");
+    }
+
+    fn invalidate_scene_singletons(&mut self) {
+        self.stage_state_manager.invalidate();
+        self.time_attack_manager.invalidate();
+        self.stage_manager.invalidate();
+        self.game_manager.invalidate();
+    }
+
+    fn profile_start(&self) -> Option<profile_clock::Start> {
+        self.profiling.then(profile_clock::now)
+    }
+
+    fn profile_end(&mut self, section: Section, start: Option<profile_clock::Start>) {
+        self.profiler
+            .record(section, start.map(profile_clock::micros_since));
+    }
+}
+
+#[derive(Default)]
+struct PlayerStatsOffsets {
+    health: Option<u32>,
+    lives: Option<u32>,
+    pac_dots: Option<u32>,
+    fruit: Option<u32>,
+    tokens: Option<u32>,
+    keys: Option<u32>,
+}
+
+impl PlayerStatsOffsets {
+    fn is_ready(&self) -> bool {
+        self.health.is_some()
+            && self.lives.is_some()
+            && self.pac_dots.is_some()
+            && self.fruit.is_some()
+            && self.tokens.is_some()
+            && self.keys.is_some()
+    }
+}
+
+// the collected pac-dots and fruit are shared with the player stats, these are the rest for the stage 100%
+#[derive(Default)]
+struct CollectibleOffsets {
+    pac_dots_total: Option<u32>,
+    fruit_total: Option<u32>,
+    tokens: Option<u32>,
+    tokens_total: Option<u32>,
+    galaxian_pieces: Option<u32>,
+    galaxian_pieces_total: Option<u32>,
+}
+
+impl CollectibleOffsets {
+    fn is_ready(&self) -> bool {
+        self.pac_dots_total.is_some()
+            && self.fruit_total.is_some()
+            && self.tokens.is_some()
+            && self.tokens_total.is_some()
+            && self.galaxian_pieces.is_some()
+            && self.galaxian_pieces_total.is_some()
+    }
+}
+
+#[derive(Default)]
+struct SaveDataOffsets {
+    stage_clear: Option<u32>,
+    past_unlock: Option<u32>,
+    sonic_unlock: Option<u32>,
+    museum_buy: Option<u32>,
+}
+
+impl SaveDataOffsets {
+    fn is_ready(&self) -> bool {
+        self.stage_clear.is_some()
+            && self.past_unlock.is_some()
+            && self.sonic_unlock.is_some()
+            && self.museum_buy.is_some()
+    }
+}
+
+// the settings that decide what gets read, the tick benchmark sets them without the settings gui
+#[derive(Clone, Copy)]
+pub struct ReadSettings {
+    pub timer_mode: TimerMode,
+    pub remove_loads_il: bool,
+    pub profile_tick_cost: bool,
+}
+
+impl From<&Settings> for ReadSettings {
+    fn from(settings: &Settings) -> Self {
+        Self {
+            timer_mode: settings.timer_mode.current,
+            remove_loads_il: settings.remove_loads_il,
+            profile_tick_cost: settings.profile_tick_cost,
+        }
     }
 }
 
 pub fn update_watchers(
-    game: &Process,
+    game: &impl GameMemory,
     addresses: &mut Memory,
     watchers: &mut Watchers,
-    settings: &Settings,
+    settings: ReadSettings,
 ) {
-    /* let title_scene_step = addresses
-        .title_scene_step
-        .deref::<u64>(game, &addresses.il2cpp_module, &addresses.game_assembly)
+    /* let title_scene_step = game.deref::<u64, _>(&addresses.title_scene_step)
         .unwrap_or_default();
     asr::timer::set_variable_int("TITLE STEP", title_scene_step); */
 
+    update_profiling_state(addresses, settings);
+
+    let profile = addresses.profile_start();
     let level_id = addresses
-        .level_id
-        .deref::<u32>(game, &addresses.il2cpp_module, &addresses.game_assembly)
+        .scene_manager
+        .read::<u32>(game, &mut addresses.current_scene)
         .unwrap_or(100_000)
         .into();
     watchers.level_id.update_infallible(level_id);
 
     let is_loading = addresses
-        .is_loading
-        .deref::<bool>(game, &addresses.il2cpp_module, &addresses.game_assembly)
+        .scene_manager
+        .read::<bool>(game, &mut addresses.processing)
         .unwrap_or_default();
     watchers.is_loading.update_infallible(is_loading);
+    addresses.profile_end(Section::Scene, profile);
+
+    // the managers of the old scene are destroyed with it, the new ones may only be set when the load ends
+    let is_loading_pair = watchers.is_loading.pair.unwrap_or_default();
+    if watchers.level_id.pair.unwrap_or_default().changed()
+        || (is_loading_pair.changed() && !is_loading_pair.current)
+    {
+        addresses.invalidate_scene_singletons();
+    }
 
     // refresh stage manager path on level change, but not on level select to avoid lagging the load detection
     let curr_level_pair = watchers.level_id.pair.unwrap_or_default();
-    if curr_level_pair.changed() && !level_is_stage_select(curr_level_pair.current) {
+    if curr_level_pair.changed() && !curr_level_pair.current.is_stage_select() {
+        let profile = addresses.profile_start();
         addresses.refresh_stage_manager_pointer_path();
+        if !addresses.player_stats_offsets.is_ready() {
+            addresses.refresh_player_stats_offsets(game);
+        }
+        if !addresses.collectible_offsets.is_ready() {
+            addresses.refresh_collectible_offsets(game);
+        }
+        if !addresses.save_data_offsets.is_ready() {
+            addresses.refresh_save_data_offsets(game);
+        }
+        addresses.profile_end(Section::Refresh, profile);
     }
 
+    let profile = addresses.profile_start();
     let checkpoint = addresses
-        .checkpoint
-        .deref::<i32>(game, &addresses.il2cpp_module, &addresses.game_assembly)
+        .stage_state_manager
+        .read_valid::<i32>(game, &mut addresses.check_point_priority, |checkpoint| {
+            *checkpoint >= -1
+        })
         .unwrap_or_default();
     watchers.checkpoint.update_infallible(checkpoint);
+    addresses.profile_end(Section::Checkpoint, profile);
 
-    let players_array_pointer_res = addresses.players_array.deref::<u64>(
-        game,
-        &addresses.il2cpp_module,
-        &addresses.game_assembly,
-    );
-    if let Ok(players_array_pointer) = players_array_pointer_res {
+    let profile = addresses.profile_start();
+    let players_array_pointer_res = game.deref::<u64, _>(&addresses.players_array);
+    if let Some(players_array_pointer) = players_array_pointer_res {
         let player_state = get_player1_state(game, players_array_pointer, addresses);
         watchers.player_state.update_infallible(player_state);
         asr::timer::set_variable("Player State", player_state_to_string(player_state));
     }
+    addresses.profile_end(Section::Player, profile);
 
     asr::timer::set_variable("LevelEnum", level_id.to_string());
     asr::timer::set_variable_int("Checkpoint", checkpoint);
@@ -192,10 +343,11 @@ pub fn update_watchers(
         asr::timer::set_variable("Loading", "False");
     }
 
-    if !level_is_stage_select(level_id) {
+    if !level_id.is_stage_select() {
+        let profile = addresses.profile_start();
         let stage_manager_state_int = addresses
-            .stage_manager_state
-            .deref::<u32>(game, &addresses.il2cpp_module, &addresses.game_assembly)
+            .stage_manager
+            .read_valid::<u32>(game, &mut addresses.stage_step, |step| *step <= 10)
             .unwrap_or_default();
         let stage_manager_state = get_stage_manager_state(stage_manager_state_int);
         watchers.stage_state.update_infallible(stage_manager_state);
@@ -204,52 +356,93 @@ pub fn update_watchers(
             "Stage Manager State",
             stage_state_to_string(stage_manager_state),
         );
+        addresses.profile_end(Section::StageState, profile);
+
+        let profile = addresses.profile_start();
+        if let Some(players_array_pointer) = players_array_pointer_res {
+            update_player_stats(game, addresses, watchers, players_array_pointer);
+        }
+        addresses.profile_end(Section::PlayerStats, profile);
+
+        let profile = addresses.profile_start();
+        update_stage_collectibles(game, addresses, watchers);
+        addresses.profile_end(Section::Collectibles, profile);
 
         // only refresh the boss state when stage manager is on the initial fade end state, aka start of the level
         if watchers.stage_state.pair.unwrap_or_default().changed()
             && watchers.stage_state.pair.unwrap_or_default().current == StageState::InitEndFade
-            && level_is_boss_stage(curr_level_pair.current)
+            && curr_level_pair.current.is_boss_stage()
         {
+            let profile = addresses.profile_start();
             addresses.refresh_boss_state_pointer_path();
+            addresses.profile_end(Section::Refresh, profile);
         }
     }
 
-    match settings.timer_mode.current {
-        TimerMode::IL => {
-            if settings.split_boss_phase {
-                let boss_state = get_boss_state(game, addresses, &level_id);
-                watchers.boss_state.update_infallible(boss_state);
-                asr::timer::set_variable_int("Boss State", boss_state);
-            }
+    // the individual levels only need it for their optional load removal
+    if matches!(
+        settings.timer_mode,
+        TimerMode::FullGame | TimerMode::ILSeries
+    ) || (settings.timer_mode == TimerMode::IL && settings.remove_loads_il)
+    {
+        // get the loading animation progress from the UI for a more accurate (normal) level start time
+        let profile = addresses.profile_start();
+        let loading_ui_add_res = game.deref::<u64, _>(&addresses.loadscreen_ui_pointer);
+        if let Some(ui_add) = loading_ui_add_res {
+            match addresses.load_progress_gui_offset {
+                Some(offset) => {
+                    // m_fProgPrev
+                    let load_progress_pc =
+                        game.read::<f32>(ui_add + offset as u64).unwrap_or_default();
+                    watchers
+                        .load_ui_progress
+                        .update_infallible(load_progress_pc);
+                    asr::timer::set_variable_float("UI Load Anim Progress", load_progress_pc);
+                }
+                None => {
+                    addresses.refresh_gui_load_prog_offset(game);
+                }
+            };
         }
+        addresses.profile_end(Section::LoadUi, profile);
+    }
+
+    // the stage clock, only used while the stage is being played so a failed read is fine
+    // full game can be timed with it and every mode checks the time scale against it
+    let profile = addresses.profile_start();
+    let stage_time = addresses
+        .stage_manager
+        .read_valid::<f32>(game, &mut addresses.play_time, |time| {
+            time.is_finite() && *time >= 0.
+        })
+        .unwrap_or_default();
+    watchers.stage_time.update_infallible(stage_time);
+    addresses.profile_end(Section::StageClock, profile);
+
+    // the boss variables are there in every mode, only the boss stages read anything.
+    // the pointer path is only rebuilt when the boss stage starts, so this stays cheap
+    let profile = addresses.profile_start();
+    update_boss_watchers(game, addresses, watchers, &level_id);
+    addresses.profile_end(Section::Boss, profile);
+
+    match settings.timer_mode {
+        // the boss is all it needs, and that's read for every mode
+        TimerMode::IL => {}
         TimerMode::FullGame | TimerMode::ILSeries => {
-            // get the loading animation progress from the UI for a more accurate (normal) level start time
-            let loading_ui_add_res = addresses.loadscreen_ui_pointer.deref::<u64>(
-                game,
-                &addresses.il2cpp_module,
-                &addresses.game_assembly,
-            );
-            if let Ok(ui_add) = loading_ui_add_res {
-                match addresses.load_progress_gui_offset {
-                    Some(offset) => {
-                        // m_fProgPrev
-                        let load_progress_pc =
-                            game.read::<f32>(ui_add + offset as u64).unwrap_or_default();
-                        watchers
-                            .load_ui_progress
-                            .update_infallible(load_progress_pc);
-                        asr::timer::set_variable_float("UI Load Anim Progress", load_progress_pc);
-                    }
-                    None => {
-                        addresses.refresh_gui_load_prog_offset(game);
-                    }
-                };
+            if settings.timer_mode == TimerMode::FullGame {
+                let profile = addresses.profile_start();
+                let progress = get_progress(game, addresses);
+                watchers.progress.update_infallible(progress);
+                asr::timer::set_variable_int("Stages Cleared", progress.cleared_stage_count());
+                asr::timer::set_variable("Eras Unlocked", progress.eras_to_string());
+                asr::timer::set_variable_int("Museum Purchases", progress.museum_purchases);
+                addresses.profile_end(Section::SaveData, profile);
             }
 
             if level_id == GameStage::Stage6_4 {
-                let spooky_qte_success = addresses
-                    .spooky_qte_success
-                    .deref::<bool>(game, &addresses.il2cpp_module, &addresses.game_assembly)
+                let profile = addresses.profile_start();
+                let spooky_qte_success = game
+                    .deref::<bool, _>(&addresses.spooky_qte_success)
                     .unwrap_or_default();
                 watchers
                     .spooky_qte_success
@@ -261,22 +454,18 @@ pub fn update_watchers(
                         false => "No",
                     },
                 );
-            }
-
-            if level_id == GameStage::Stage6_5 {
-                let boss_state = get_boss_state(game, addresses, &level_id);
-                watchers.boss_state.update_infallible(boss_state);
-                asr::timer::set_variable_int("Boss State", boss_state);
+                addresses.profile_end(Section::Boss, profile);
             }
         }
+        // the checkpoint and player state are all it needs
+        TimerMode::Segment => {}
         TimerMode::TimeTrial => {
-            let bonus_list_address_res = addresses.time_trial_bonus_list_pointer.deref::<u64>(
-                game,
-                &addresses.il2cpp_module,
-                &addresses.game_assembly,
-            );
+            let profile = addresses.profile_start();
+            let bonus_list_address = addresses
+                .time_attack_manager
+                .read::<u64>(game, &mut addresses.bonus_time_list);
 
-            if let Ok(list_pointer) = bonus_list_address_res {
+            if let Some(list_pointer) = bonus_list_address {
                 let time_trial_bonus = calculate_time_bonus(game, list_pointer);
                 watchers
                     .time_trial_bonus_time
@@ -285,8 +474,8 @@ pub fn update_watchers(
             }
 
             let time_trial_igt = addresses
-                .time_trial_igt
-                .deref::<f64>(game, &addresses.il2cpp_module, &addresses.game_assembly)
+                .time_attack_manager
+                .read::<f64>(game, &mut addresses.time_attack_time)
                 .unwrap_or_default();
             let time_trial_igt_rounded = round_no_std_f64(time_trial_igt, 2);
             watchers
@@ -294,8 +483,8 @@ pub fn update_watchers(
                 .update_infallible(time_trial_igt_rounded);
 
             let time_trial_state_raw = addresses
-                .time_trial_state
-                .deref::<u32>(game, &addresses.il2cpp_module, &addresses.game_assembly)
+                .time_attack_manager
+                .read::<u32>(game, &mut addresses.time_attack_step)
                 .unwrap_or_default();
 
             let time_trial_state = time_trial_state_int_to_enum(time_trial_state_raw);
@@ -303,23 +492,17 @@ pub fn update_watchers(
                 .time_trial_state
                 .update_infallible(time_trial_state);
 
-            if settings.split_boss_phase {
-                let boss_state = get_boss_state(game, addresses, &level_id);
-                watchers.boss_state.update_infallible(boss_state);
-                asr::timer::set_variable_int("Boss State", boss_state);
-            }
-
             asr::timer::set_variable_float("Time Trial Timer", time_trial_igt);
             time_trial_state_print_var(time_trial_state);
+            addresses.profile_end(Section::TimeTrial, profile);
         }
         TimerMode::TimeTrialMarathon => {
-            let bonus_list_address_res = addresses.time_trial_bonus_list_pointer.deref::<u64>(
-                game,
-                &addresses.il2cpp_module,
-                &addresses.game_assembly,
-            );
+            let profile = addresses.profile_start();
+            let bonus_list_address = addresses
+                .time_attack_manager
+                .read::<u64>(game, &mut addresses.bonus_time_list);
 
-            if let Ok(list_pointer) = bonus_list_address_res {
+            if let Some(list_pointer) = bonus_list_address {
                 let time_trial_bonus = calculate_time_bonus(game, list_pointer);
                 watchers
                     .time_trial_bonus_time
@@ -328,8 +511,8 @@ pub fn update_watchers(
             }
 
             let time_trial_igt = addresses
-                .time_trial_igt
-                .deref::<f64>(game, &addresses.il2cpp_module, &addresses.game_assembly)
+                .time_attack_manager
+                .read::<f64>(game, &mut addresses.time_attack_time)
                 .unwrap_or_default();
 
             let time_trial_igt_rounded = round_no_std_f64(time_trial_igt, 2);
@@ -339,14 +522,10 @@ pub fn update_watchers(
                 .update_infallible(time_trial_igt_rounded);
 
             let time_trial_state_raw = addresses
-                .time_trial_state
-                .deref::<u32>(game, &addresses.il2cpp_module, &addresses.game_assembly)
+                .time_attack_manager
+                .read::<u32>(game, &mut addresses.time_attack_step)
                 .unwrap_or_default();
 
-            let boss_state = get_boss_state(game, addresses, &level_id);
-            watchers.boss_state.update_infallible(boss_state);
-            asr::timer::set_variable_int("Boss State", boss_state);
-
             let time_trial_state = time_trial_state_int_to_enum(time_trial_state_raw);
             watchers
                 .time_trial_state
@@ -354,7 +533,85 @@ pub fn update_watchers(
 
             asr::timer::set_variable_float("Time Trial Timer", time_trial_igt);
             time_trial_state_print_var(time_trial_state);
+            addresses.profile_end(Section::TimeTrial, profile);
+        }
+    }
+
+    if addresses.profiling {
+        finish_profiled_tick(addresses, is_loading);
+    }
+}
+
+fn update_profiling_state(addresses: &mut Memory, settings: ReadSettings) {
+    if !settings.profile_tick_cost {
+        if addresses.profiling {
+            addresses.profiling = false;
+            addresses.profiler.reset();
         }
+        return;
+    }
+
+    if !profile_clock::AVAILABLE {
+        if !addresses.profiling_unavailable_logged {
+            print_message("Tick cost profiling needs a build with the profiling feature");
+            addresses.profiling_unavailable_logged = true;
+        }
+        return;
+    }
+    addresses.profiling = true;
+}
+
+fn finish_profiled_tick(addresses: &mut Memory, is_loading: bool) {
+    if let Some(tick_cost) = addresses.profiler.end_tick() {
+        let (section, _) = addresses.profiler.slowest_section();
+        asr::print_limited::<128>(&format_args!(
+            "Tick cost spike: {tick_cost:.0}µs{}, slowest read on average: {}",
+            match is_loading {
+                true => " while loading",
+                false => "",
+            },
+            section.to_string(),
+        ));
+    }
+
+    let (slowest, slowest_cost) = addresses.profiler.slowest_section();
+    asr::timer::set_variable_float("Tick cost µs", addresses.profiler.tick_average());
+    asr::timer::set_variable("Slowest read", slowest.to_string());
+    asr::timer::set_variable_float("Slowest read µs", slowest_cost);
+    for section in SECTIONS {
+        asr::timer::set_variable_float(
+            section.variable_name(),
+            addresses.profiler.section_average(section),
+        );
+    }
+}
+
+// the runtime only gives a clock to WASI builds, host builds of the modules have no clock
+#[cfg(all(feature = "profiling", target_os = "wasi"))]
+mod profile_clock {
+    use asr::time_util::Instant;
+
+    pub type Start = Instant;
+    pub const AVAILABLE: bool = true;
+
+    pub fn now() -> Start {
+        Instant::now()
+    }
+
+    pub fn micros_since(start: Start) -> u64 {
+        start.elapsed().whole_microseconds() as u64
+    }
+}
+
+#[cfg(not(all(feature = "profiling", target_os = "wasi")))]
+mod profile_clock {
+    pub type Start = ();
+    pub const AVAILABLE: bool = false;
+
+    pub fn now() -> Start {}
+
+    pub fn micros_since(_start: Start) -> u64 {
+        0
     }
 }
 
@@ -369,7 +626,7 @@ fn round_no_std_f64(number: f64, digits: u32) -> f64 {
     }
 }
 
-fn calculate_time_bonus(game: &Process, bonus_list_pointer: u64) -> u32 {
+fn calculate_time_bonus(game: &impl GameMemory, bonus_list_pointer: u64) -> u32 {
     // this is a list pointer, it is an object with the data but not 100% straightforward
 
     // relevant data in this list object:
@@ -379,8 +636,8 @@ fn calculate_time_bonus(game: &Process, bonus_list_pointer: u64) -> u32 {
     let items_pointer_res = game.read::<u64>(bonus_list_pointer + 0x10);
 
     let items_pointer = match items_pointer_res {
-        Ok(pointer) => pointer,
-        Err(_) => return 0,
+        Some(pointer) => pointer,
+        None => return 0,
     };
 
     let list_size = game
@@ -399,7 +656,11 @@ fn calculate_time_bonus(game: &Process, bonus_list_pointer: u64) -> u32 {
     total_bonus
 }
 
-fn get_player1_state(game: &Process, players_pointer: u64, addreses: &mut Memory) -> PlayerState {
+fn get_player1_state(
+    game: &impl GameMemory,
+    players_pointer: u64,
+    addreses: &mut Memory,
+) -> PlayerState {
     // all active "PlayerPacman"s are in an array, probably for 2p compatibility
     // so in the array obj, offset 0x20 is the PlayerPacman object we need, position 0
     let player_obj = game.read::<u64>(players_pointer + 0x20).unwrap_or_default();
@@ -442,7 +703,136 @@ fn get_player1_state(game: &Process, players_pointer: u64, addreses: &mut Memory
     }
 }
 
-fn player_state_to_string(player_state: PlayerState) -> &'static str {
+fn update_player_stats(
+    game: &impl GameMemory,
+    addresses: &mut Memory,
+    watchers: &mut Watchers,
+    players_pointer: u64,
+) {
+    let offsets = &addresses.player_stats_offsets;
+    let player_obj = game.read::<u64>(players_pointer + 0x20);
+    let stage_manager = addresses.stage_manager.instance(game);
+    let game_manager = addresses.game_manager.instance(game);
+
+    let health = read_stat(game, player_obj, offsets.health);
+    let lives = read_stat(game, game_manager, offsets.lives);
+    let pac_dots = read_stat(game, stage_manager, offsets.pac_dots);
+    let fruit = read_stat(game, stage_manager, offsets.fruit);
+    let tokens = read_stat(game, game_manager, offsets.tokens);
+    let keys = read_stat(game, stage_manager, offsets.keys);
+
+    watchers.health.update_infallible(health);
+    watchers.lives.update_infallible(lives);
+    watchers.pac_dots.update_infallible(pac_dots);
+    watchers.fruit.update_infallible(fruit);
+    watchers.tokens.update_infallible(tokens);
+    watchers.keys.update_infallible(keys);
+
+    asr::timer::set_variable_int("Health", health);
+    asr::timer::set_variable_int("Lives", lives);
+    asr::timer::set_variable_int("Pac-Dots", pac_dots);
+    asr::timer::set_variable_int("Fruit", fruit);
+    asr::timer::set_variable_int("Galaxian Tokens", tokens);
+    asr::timer::set_variable_int("Keys", keys);
+}
+
+fn update_stage_collectibles(
+    game: &impl GameMemory,
+    addresses: &mut Memory,
+    watchers: &mut Watchers,
+) {
+    let offsets = &addresses.collectible_offsets;
+    let stage_manager = addresses.stage_manager.instance(game);
+
+    let collectibles = StageCollectibles {
+        pac_dots: watchers.pac_dots.pair.unwrap_or_default().current,
+        pac_dots_total: read_stat(game, stage_manager, offsets.pac_dots_total),
+        fruit: watchers.fruit.pair.unwrap_or_default().current,
+        fruit_total: read_stat(game, stage_manager, offsets.fruit_total),
+        tokens: read_stat(game, stage_manager, offsets.tokens),
+        tokens_total: read_stat(game, stage_manager, offsets.tokens_total),
+        galaxian_pieces: read_stat(game, stage_manager, offsets.galaxian_pieces),
+        galaxian_pieces_total: read_stat(game, stage_manager, offsets.galaxian_pieces_total),
+    };
+    let stage_complete = collectibles.is_complete();
+    watchers.stage_complete.update_infallible(stage_complete);
+
+    asr::timer::set_variable_int("Pac-Dots Total", collectibles.pac_dots_total);
+    asr::timer::set_variable_int("Fruit Total", collectibles.fruit_total);
+    asr::timer::set_variable_int("Stage Tokens", collectibles.tokens);
+    asr::timer::set_variable_int("Stage Tokens Total", collectibles.tokens_total);
+    asr::timer::set_variable_int("Galaxian Pieces", collectibles.galaxian_pieces);
+    asr::timer::set_variable_int("Galaxian Pieces Total", collectibles.galaxian_pieces_total);
+    asr::timer::set_variable(
+        "Stage 100%",
+        match stage_complete {
+            true => "Yes",
+            false => "No",
+        },
+    );
+}
+
+fn get_progress(game: &impl GameMemory, addresses: &Memory) -> Progress {
+    let offsets = &addresses.save_data_offsets;
+    let save_data_res = game.deref::<u64, _>(&addresses.save_data);
+    let save_data = match save_data_res {
+        Some(save_data) => save_data,
+        None => return Progress::default(),
+    };
+
+    let read_flag = |offset: Option<u32>| match offset {
+        Some(offset) => game
+            .read::<bool>(save_data + offset as u64)
+            .unwrap_or_default(),
+        None => false,
+    };
+    let read_flag_array = |offset: Option<u32>| match offset {
+        Some(offset) => match game.read::<u64>(save_data + offset as u64) {
+            Some(array) => read_bool_array_bits(game, array),
+            None => 0,
+        },
+        None => 0,
+    };
+
+    Progress {
+        cleared_stages: read_flag_array(offsets.stage_clear),
+        past_unlocked: read_flag(offsets.past_unlock),
+        sonic_unlocked: read_flag(offsets.sonic_unlock),
+        museum_purchases: read_flag_array(offsets.museum_buy).count_ones(),
+    }
+}
+
+fn read_bool_array_bits(game: &impl GameMemory, array_pointer: u64) -> u64 {
+    // in the array object:
+    // 0x18: length of the array
+    // 0x20: the bools, one byte each
+    // only the bools that are in the array are read, a short array can sit at the end of a page
+    let length = game
+        .read::<u32>(array_pointer + 0x18)
+        .unwrap_or_default()
+        .min(64) as usize;
+    let mut flags = [0; 64];
+    if !game.read_into_buf(array_pointer + 0x20, &mut flags[..length]) {
+        return 0;
+    }
+
+    flags[..length]
+        .iter()
+        .enumerate()
+        .filter(|(_, flag)| **flag != 0)
+        .fold(0, |bits, (i, _)| bits | (1 << i))
+}
+
+fn read_stat(game: &impl GameMemory, instance: Option<u64>, offset: Option<u32>) -> i32 {
+    match (instance, offset) {
+        (Some(instance), Some(offset)) => game
+            .read::<i32>(instance + offset as u64)
+            .unwrap_or_default(),
+        _ => 0,
+    }
+}
+
+pub fn player_state_to_string(player_state: PlayerState) -> &'static str {
     match player_state {
         PlayerState::None => "None",
         PlayerState::Control => "Control",
@@ -487,7 +877,7 @@ fn get_stage_manager_state(state: u32) -> StageState {
     }
 }
 
-fn stage_state_to_string(state: StageState) -> &'static str {
+pub fn stage_state_to_string(state: StageState) -> &'static str {
     match state {
         StageState::None => "None",
         StageState::InitOnFade => "Init On Fade",
@@ -504,33 +894,53 @@ fn stage_state_to_string(state: StageState) -> &'static str {
     }
 }
 
-fn get_boss_state(game: &Process, addresses: &Memory, level_id: &GameStage) -> u32 {
+fn get_boss_state(game: &impl GameMemory, addresses: &Memory, level_id: &GameStage) -> u32 {
     let mut boss_state = 0;
 
-    if *level_id == GameStage::Stage1_4
-        || *level_id == GameStage::Stage2_4
-        || *level_id == GameStage::Stage3_4
-        || *level_id == GameStage::Stage4_4
-        || *level_id == GameStage::Stage5_4
-        || *level_id == GameStage::Stage6_4
-        || *level_id == GameStage::Stage6_5
-        || *level_id == GameStage::Stage1_4Past
-        || *level_id == GameStage::Stage2_4Past
-        || *level_id == GameStage::Stage3_4Past
-        || *level_id == GameStage::Stage4_4Past
-        || *level_id == GameStage::Stage5_4Past
-        || *level_id == GameStage::Stage6_4Past
-        || *level_id == GameStage::StageSonic3
-    {
-        boss_state = addresses
-            .boss_state
-            .deref::<u32>(game, &addresses.il2cpp_module, &addresses.game_assembly)
+    if level_id.is_boss_stage() {
+        boss_state = game
+            .deref::<u32, _>(&addresses.boss_state)
             .unwrap_or_default();
     }
 
     boss_state
 }
 
+fn get_boss_hp(game: &impl GameMemory, addresses: &Memory, level_id: &GameStage) -> (i32, i32) {
+    if !level_id.is_boss_stage() {
+        return (0, 0);
+    }
+
+    let boss_hp = game.deref::<i32, _>(&addresses.boss_hp).unwrap_or_default();
+    let boss_max_hp = game
+        .deref::<i32, _>(&addresses.boss_max_hp)
+        .unwrap_or_default();
+
+    (boss_hp, boss_max_hp)
+}
+
+fn update_boss_watchers(
+    game: &impl GameMemory,
+    addresses: &Memory,
+    watchers: &mut Watchers,
+    level_id: &GameStage,
+) {
+    let boss_state = get_boss_state(game, addresses, level_id);
+    watchers.boss_state.update_infallible(boss_state);
+    let (boss_hp, boss_max_hp) = get_boss_hp(game, addresses, level_id);
+    watchers.boss_hp.update_infallible(boss_hp);
+    watchers.boss_max_hp.update_infallible(boss_max_hp);
+
+    // the watchers go back to 0 outside of the boss stages, the variables keep the last fight
+    if let Some(fight) = level_id.boss_fight() {
+        asr::timer::set_variable_int("Boss State", boss_state);
+        asr::timer::set_variable("Boss", fight.boss);
+        asr::timer::set_variable("Boss Phase", level_id.boss_phase_name(boss_state));
+        asr::timer::set_variable_int("Boss HP", boss_hp);
+        asr::timer::set_variable_int("Boss Max HP", boss_max_hp);
+    }
+}
+
 fn time_trial_state_int_to_enum(time_trial_state_raw: u32) -> TimeTrialState {
     match time_trial_state_raw {
         0 => TimeTrialState::None,
@@ -546,14 +956,18 @@ fn time_trial_state_int_to_enum(time_trial_state_raw: u32) -> TimeTrialState {
 fn time_trial_state_print_var(time_trial_state: TimeTrialState) {
     asr::timer::set_variable(
         "Time Trial State",
-        match time_trial_state {
-            TimeTrialState::None => "None",
-            TimeTrialState::ReadyInit => "Ready_Init",
-            TimeTrialState::ReadyWait => "Ready_Wait",
-            TimeTrialState::TA => "TA",
-            TimeTrialState::Pause => "Pause",
-            TimeTrialState::End => "End",
-            TimeTrialState::Unknown => "Unknown",
-        },
+        time_trial_state_to_string(time_trial_state),
     );
 }
+
+pub fn time_trial_state_to_string(time_trial_state: TimeTrialState) -> &'static str {
+    match time_trial_state {
+        TimeTrialState::None => "None",
+        TimeTrialState::ReadyInit => "Ready_Init",
+        TimeTrialState::ReadyWait => "Ready_Wait",
+        TimeTrialState::TA => "TA",
+        TimeTrialState::Pause => "Pause",
+        TimeTrialState::End => "End",
+        TimeTrialState::Unknown => "Unknown",
+    }
+}
@@ -0,0 +1,141 @@
+// rolling per-tick cost of the memory reads in update_watchers
+// the section times are measured by the caller, with the runtime clock of the profiling build
+
+// roughly a second of ticks at the default rate
+const ROLLING_WINDOW: f64 = 120.;
+// a tick this many times slower than the average is logged
+const SPIKE_FACTOR: f64 = 4.;
+// ticks faster than this are never a spike, no matter the average
+const SPIKE_MIN_MICROS: f64 = 1000.;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    Scene,
+    Checkpoint,
+    Player,
+    StageState,
+    PlayerStats,
+    Collectibles,
+    LoadUi,
+    StageClock,
+    SaveData,
+    Boss,
+    TimeTrial,
+    Refresh,
+}
+
+const SECTION_COUNT: usize = 12;
+
+pub const SECTIONS: [Section; SECTION_COUNT] = [
+    Section::Scene,
+    Section::Checkpoint,
+    Section::Player,
+    Section::StageState,
+    Section::PlayerStats,
+    Section::Collectibles,
+    Section::LoadUi,
+    Section::StageClock,
+    Section::SaveData,
+    Section::Boss,
+    Section::TimeTrial,
+    Section::Refresh,
+];
+
+impl Section {
+    pub fn to_string(self) -> &'static str {
+        match self {
+            Section::Scene => "Scene",
+            Section::Checkpoint => "Checkpoint",
+            Section::Player => "Player",
+            Section::StageState => "Stage State",
+            Section::PlayerStats => "Player Stats",
+            Section::Collectibles => "Collectibles",
+            Section::LoadUi => "Load UI",
+            Section::StageClock => "Stage Clock",
+            Section::SaveData => "Save Data",
+            Section::Boss => "Boss",
+            Section::TimeTrial => "Time Trial",
+            Section::Refresh => "Pointer Refresh",
+        }
+    }
+
+    pub fn variable_name(self) -> &'static str {
+        match self {
+            Section::Scene => "Read µs: Scene",
+            Section::Checkpoint => "Read µs: Checkpoint",
+            Section::Player => "Read µs: Player",
+            Section::StageState => "Read µs: Stage State",
+            Section::PlayerStats => "Read µs: Player Stats",
+            Section::Collectibles => "Read µs: Collectibles",
+            Section::LoadUi => "Read µs: Load UI",
+            Section::StageClock => "Read µs: Stage Clock",
+            Section::SaveData => "Read µs: Save Data",
+            Section::Boss => "Read µs: Boss",
+            Section::TimeTrial => "Read µs: Time Trial",
+            Section::Refresh => "Read µs: Pointer Refresh",
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Profiler {
+    // cost of the sections measured in the current tick
+    tick_sections: [f64; SECTION_COUNT],
+    // rolling averages, a section that isn't read in a tick counts as 0
+    section_averages: [f64; SECTION_COUNT],
+    tick_average: f64,
+    ticks: u64,
+}
+
+impl Profiler {
+    pub fn record(&mut self, section: Section, micros: Option<u64>) {
+        if let Some(micros) = micros {
+            self.tick_sections[section as usize] += micros as f64;
+        }
+    }
+
+    // closes the tick, returns its cost if it was a spike
+    pub fn end_tick(&mut self) -> Option<f64> {
+        let tick_cost: f64 = self.tick_sections.iter().sum();
+        let is_spike = self.ticks >= ROLLING_WINDOW as u64
+            && tick_cost > SPIKE_MIN_MICROS
+            && tick_cost > self.tick_average * SPIKE_FACTOR;
+
+        // plain average until the window is filled, so the first ticks don't start from 0
+        let weight = 1. / ROLLING_WINDOW.min(self.ticks as f64 + 1.);
+        self.tick_average += (tick_cost - self.tick_average) * weight;
+        for (average, cost) in self
+            .section_averages
+            .iter_mut()
+            .zip(self.tick_sections.iter_mut())
+        {
+            *average += (*cost - *average) * weight;
+            *cost = 0.;
+        }
+        self.ticks += 1;
+
+        is_spike.then_some(tick_cost)
+    }
+
+    pub fn tick_average(&self) -> f64 {
+        self.tick_average
+    }
+
+    pub fn section_average(&self, section: Section) -> f64 {
+        self.section_averages[section as usize]
+    }
+
+    pub fn slowest_section(&self) -> (Section, f64) {
+        SECTIONS.iter().zip(self.section_averages.iter()).fold(
+            (Section::Scene, 0.),
+            |slowest, (section, average)| match *average > slowest.1 {
+                true => (*section, *average),
+                false => slowest,
+            },
+        )
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
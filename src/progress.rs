@@ -0,0 +1,32 @@
+use crate::stages::{GameStage, STAGE_SAVE_ORDER};
+
+// snapshot of the save file the game keeps in memory, one bit per entry in STAGE_SAVE_ORDER
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub struct Progress {
+    pub cleared_stages: u64,
+    pub past_unlocked: bool,
+    pub sonic_unlocked: bool,
+    pub museum_purchases: u32,
+}
+
+impl Progress {
+    pub fn is_stage_cleared(&self, stage: GameStage) -> bool {
+        match STAGE_SAVE_ORDER.iter().position(|s| *s == stage) {
+            Some(index) => self.cleared_stages & (1 << index) != 0,
+            None => false,
+        }
+    }
+
+    pub fn cleared_stage_count(&self) -> u32 {
+        self.cleared_stages.count_ones()
+    }
+
+    pub fn eras_to_string(&self) -> &'static str {
+        match (self.past_unlocked, self.sonic_unlocked) {
+            (false, false) => "Present",
+            (true, false) => "Present, Past",
+            (false, true) => "Present, Sonic",
+            (true, true) => "Present, Past, Sonic",
+        }
+    }
+}
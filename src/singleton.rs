@@ -0,0 +1,94 @@
+use asr::game_engine::unity::il2cpp::UnityPointer;
+use bytemuck::CheckedBitPattern;
+
+use crate::game::GameMemory;
+
+// a manager instance found through its `s_sInstance`, walked once and then reused every tick
+// the managers are recreated with the scene, so it has to be invalidated on a scene change.
+// a failed read or an impossible value also drops it so the next tick walks the static again
+pub struct Singleton {
+    class_name: &'static str,
+    parents: usize,
+    pointer: UnityPointer<1>,
+    instance: Option<u64>,
+}
+
+// field of a singleton, the offset comes from the class metadata the first time it's read
+pub struct Field {
+    name: &'static str,
+    offset: Option<u32>,
+}
+
+impl Field {
+    pub const fn new(name: &'static str) -> Self {
+        Self { name, offset: None }
+    }
+}
+
+impl Singleton {
+    pub fn new(class_name: &'static str, parents: usize) -> Self {
+        Self {
+            class_name,
+            parents,
+            pointer: UnityPointer::new(class_name, parents, &["s_sInstance"]),
+            instance: None,
+        }
+    }
+
+    pub fn instance(&mut self, game: &impl GameMemory) -> Option<u64> {
+        if self.instance.is_none() {
+            // the static is null until the manager is created
+            self.instance = game
+                .deref::<u64, _>(&self.pointer)
+                .filter(|instance| *instance != 0);
+        }
+        self.instance
+    }
+
+    pub fn read<T: CheckedBitPattern>(
+        &mut self,
+        game: &impl GameMemory,
+        field: &mut Field,
+    ) -> Option<T> {
+        let instance = self.instance(game)?;
+        if field.offset.is_none() {
+            field.offset = game.field_offset(self.class_name, field.name);
+        }
+
+        let value = game.read::<T>(instance + field.offset? as u64);
+        if value.is_none() {
+            self.invalidate();
+        }
+        value
+    }
+
+    // for the fields with a known range, a value out of it means the instance or the offset is stale
+    pub fn read_valid<T: CheckedBitPattern>(
+        &mut self,
+        game: &impl GameMemory,
+        field: &mut Field,
+        valid: impl Fn(&T) -> bool,
+    ) -> Option<T> {
+        let value = self.read::<T>(game, field)?;
+        if valid(&value) {
+            return Some(value);
+        }
+        self.invalidate();
+        field.offset = None;
+        None
+    }
+
+    pub fn invalidate(&mut self) {
+        self.instance = None;
+    }
+
+    // UnityPointer::new is very slow! only for the managers that change class between levels.
+    // the field offsets come from the old class, so the fields read from it are looked up again
+    pub fn refresh(&mut self, fields: &mut [&mut Field]) {
+        self.pointer = UnityPointer::new(self.class_name, self.parents, &["s_sInstance"]);
+        self.instance = None;
+        for field in fields {
+            field.offset = None;
+        }
+    }
+}
@@ -0,0 +1,35 @@
+// sums the in-game stage clock over a full game run
+// the clock starts over on every stage and on restarts, so the finished attempts are kept in `accumulated`
+#[derive(Default)]
+pub struct StageClock {
+    accumulated: f64,
+    stage_time: f64,
+}
+
+impl StageClock {
+    pub fn reset(&mut self) {
+        self.accumulated = 0.;
+        self.stage_time = 0.;
+    }
+
+    // `stage_time` is only given while the stage is being played, the clock of a stage that is
+    // unloading or still loading is not reliable
+    pub fn update(&mut self, level_changed: bool, stage_time: Option<f64>) {
+        if level_changed {
+            self.accumulated += self.stage_time;
+            self.stage_time = 0.;
+        }
+
+        if let Some(time) = stage_time {
+            // the clock going back means the stage was restarted, keep the time of the previous attempt
+            if time < self.stage_time {
+                self.accumulated += self.stage_time;
+            }
+            self.stage_time = time;
+        }
+    }
+
+    pub fn total(&self) -> f64 {
+        self.accumulated + self.stage_time
+    }
+}
@@ -271,3 +271,274 @@ impl GameStage {
         }
     }
 }
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Era {
+    Present,
+    Past,
+    Sonic,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum StageKind {
+    Normal,
+    Boss,
+    Maze,
+    Bonus,
+    // pac-village, where the game starts and the worlds are reached from
+    Hub,
+    StageSelect,
+    Menu,
+}
+
+impl GameStage {
+    pub fn era(self) -> Era {
+        match self {
+            GameStage::StageSelectPast
+            | GameStage::Stage1_1Past
+            | GameStage::Stage1_4Past
+            | GameStage::Stage2_3Past
+            | GameStage::Stage2_4Past
+            | GameStage::Stage3_3Past
+            | GameStage::Stage3_4Past
+            | GameStage::Stage4_2Past
+            | GameStage::Stage4_4Past
+            | GameStage::Stage5_2Past
+            | GameStage::Stage5_4Past
+            | GameStage::Stage6_3Past
+            | GameStage::Stage6_4Past => Era::Past,
+            GameStage::StageSelectSonic
+            | GameStage::CreditSonic
+            | GameStage::StageSonic1
+            | GameStage::StageSonic2
+            | GameStage::StageSonic3 => Era::Sonic,
+            _ => Era::Present,
+        }
+    }
+
+    pub fn kind(self) -> StageKind {
+        match self {
+            GameStage::Stage1_4
+            | GameStage::Stage2_4
+            | GameStage::Stage3_4
+            | GameStage::Stage4_4
+            | GameStage::Stage5_4
+            | GameStage::Stage6_4
+            | GameStage::Stage6_5
+            | GameStage::Stage1_4Past
+            | GameStage::Stage2_4Past
+            | GameStage::Stage3_4Past
+            | GameStage::Stage4_4Past
+            | GameStage::Stage5_4Past
+            | GameStage::Stage6_4Past
+            | GameStage::StageSonic3 => StageKind::Boss,
+            GameStage::Stage1_1
+            | GameStage::Stage1_2
+            | GameStage::Stage1_3
+            | GameStage::Stage1_1Past
+            | GameStage::Stage2_1
+            | GameStage::Stage2_2
+            | GameStage::Stage2_3
+            | GameStage::Stage2_3Past
+            | GameStage::Stage3_1
+            | GameStage::Stage3_2
+            | GameStage::Stage3_3
+            | GameStage::Stage3_3Past
+            | GameStage::Stage4_1
+            | GameStage::Stage4_2
+            | GameStage::Stage4_3
+            | GameStage::Stage4_2Past
+            | GameStage::Stage5_1
+            | GameStage::Stage5_2
+            | GameStage::Stage5_3
+            | GameStage::Stage5_2Past
+            | GameStage::Stage6_1
+            | GameStage::Stage6_2
+            | GameStage::Stage6_3
+            | GameStage::Stage6_3Past
+            | GameStage::StageSonic1
+            | GameStage::StageSonic2 => StageKind::Normal,
+            GameStage::MazeStage1_2
+            | GameStage::MazeStage1_3
+            | GameStage::MazeStage2_1
+            | GameStage::MazeStage2_2
+            | GameStage::MazeStage2_3
+            | GameStage::MazeStage3_1
+            | GameStage::MazeStage3_2
+            | GameStage::MazeStage3_3
+            | GameStage::MazeStage4_1
+            | GameStage::MazeStage4_2
+            | GameStage::MazeStage4_3
+            | GameStage::MazeStage5_1
+            | GameStage::MazeStage5_2
+            | GameStage::MazeStage6_1
+            | GameStage::MazeStage6_3 => StageKind::Maze,
+            GameStage::BonusStage1
+            | GameStage::BonusStage2
+            | GameStage::BonusStage3
+            | GameStage::BonusStage4
+            | GameStage::BonusStage5
+            | GameStage::BonusStage6 => StageKind::Bonus,
+            GameStage::StageSelect | GameStage::StageSelectPast | GameStage::StageSelectSonic => {
+                StageKind::StageSelect
+            }
+            GameStage::PacVillage => StageKind::Hub,
+            _ => StageKind::Menu,
+        }
+    }
+
+    pub fn is_boss_stage(self) -> bool {
+        self.kind() == StageKind::Boss
+    }
+
+    pub fn is_stage_select(self) -> bool {
+        self.kind() == StageKind::StageSelect
+    }
+
+    // an actual level from the past era, not the past stage select
+    pub fn is_past_stage(self) -> bool {
+        self.era() == Era::Past && self.kind() != StageKind::StageSelect
+    }
+
+    // the levels with collectibles, these are the ones that count for the completion percentage
+    pub fn has_collectibles(self) -> bool {
+        self.kind() == StageKind::Normal && self.era() != Era::Sonic && self.save_index().is_some()
+    }
+
+    // where the stage is in the save data, for keeping something per stage
+    pub fn save_index(self) -> Option<usize> {
+        STAGE_SAVE_ORDER.iter().position(|stage| *stage == self)
+    }
+}
+
+// the level a scene is counted under when keeping something per level. mazes and bonus stages are
+// part of the level they're entered from, everything that isn't a level has none
+#[derive(Default)]
+pub struct ParentLevel {
+    level: Option<GameStage>,
+}
+
+impl ParentLevel {
+    pub fn update(&mut self, scene: GameStage) -> Option<GameStage> {
+        if !matches!(scene.kind(), StageKind::Maze | StageKind::Bonus) {
+            self.level = scene.save_index().map(|_| scene);
+        }
+        self.level
+    }
+}
+
+// the order of `SaveData.m_stageClear`. the save data has no stage ids, this is the order of the
+// scene ids above, which are the game's own, with the scenes that aren't levels left out.
+// it's not confirmed against a save with known clears yet, so the first clear splits are opt in
+pub const STAGE_SAVE_ORDER: &[GameStage] = &[
+    GameStage::Stage1_1,
+    GameStage::Stage1_2,
+    GameStage::Stage1_3,
+    GameStage::Stage1_4,
+    GameStage::Stage1_1Past,
+    GameStage::Stage1_4Past,
+    GameStage::Stage2_1,
+    GameStage::Stage2_2,
+    GameStage::Stage2_3,
+    GameStage::Stage2_4,
+    GameStage::Stage2_3Past,
+    GameStage::Stage2_4Past,
+    GameStage::Stage3_1,
+    GameStage::Stage3_2,
+    GameStage::Stage3_3,
+    GameStage::Stage3_4,
+    GameStage::Stage3_3Past,
+    GameStage::Stage3_4Past,
+    GameStage::Stage4_1,
+    GameStage::Stage4_2,
+    GameStage::Stage4_3,
+    GameStage::Stage4_4,
+    GameStage::Stage4_2Past,
+    GameStage::Stage4_4Past,
+    GameStage::Stage5_1,
+    GameStage::Stage5_2,
+    GameStage::Stage5_3,
+    GameStage::Stage5_4,
+    GameStage::Stage5_2Past,
+    GameStage::Stage5_4Past,
+    GameStage::Stage6_1,
+    GameStage::Stage6_2,
+    GameStage::Stage6_3,
+    GameStage::Stage6_4,
+    GameStage::Stage6_3Past,
+    GameStage::Stage6_4Past,
+    GameStage::Stage6_5,
+    GameStage::StageSonic1,
+    GameStage::StageSonic2,
+    GameStage::StageSonic3,
+];
+
+// raw `BossBase.m_state` values are the phase number, with 0 being the intro before the fight starts.
+// every boss has its own table so a phase can be named once it's been told apart in game
+pub struct BossFight {
+    pub boss: &'static str,
+    pub phases: &'static [&'static str],
+}
+
+// the final boss fights end on state 4, the same state the time trial marathon splits on
+const TOC_MAN: BossFight = BossFight {
+    boss: "Toc-Man",
+    phases: &["Intro", "Phase 1", "Phase 2", "Phase 3", "Defeated"],
+};
+// the last phase is the QTE, `BossSpooky.m_qteSuccess` is set when it's done
+const SPOOKY: BossFight = BossFight {
+    boss: "Spooky",
+    phases: &["Intro", "Phase 1", "Phase 2", "Phase 3 - QTE", "Defeated"],
+};
+const SPOOKY_PAST: BossFight = BossFight {
+    boss: "Spooky (Past)",
+    phases: &["Intro", "Phase 1", "Phase 2", "Phase 3", "Defeated"],
+};
+const SONIC_BOSS: BossFight = BossFight {
+    boss: "Sonic Boss",
+    phases: &["Intro", "Phase 1", "Phase 2", "Phase 3", "Defeated"],
+};
+const WORLD_BOSS_PHASES: &[&str] = &[
+    "Intro", "Phase 1", "Phase 2", "Phase 3", "Phase 4", "Phase 5", "Phase 6",
+];
+
+impl GameStage {
+    pub fn boss_fight(self) -> Option<BossFight> {
+        let boss = match self {
+            GameStage::Stage6_5 => return Some(TOC_MAN),
+            GameStage::Stage6_4 => return Some(SPOOKY),
+            GameStage::Stage6_4Past => return Some(SPOOKY_PAST),
+            GameStage::StageSonic3 => return Some(SONIC_BOSS),
+            GameStage::Stage1_4 => "World 1 Boss",
+            GameStage::Stage2_4 => "World 2 Boss",
+            GameStage::Stage3_4 => "World 3 Boss",
+            GameStage::Stage4_4 => "World 4 Boss",
+            GameStage::Stage5_4 => "World 5 Boss",
+            GameStage::Stage1_4Past => "World 1 Boss (Past)",
+            GameStage::Stage2_4Past => "World 2 Boss (Past)",
+            GameStage::Stage3_4Past => "World 3 Boss (Past)",
+            GameStage::Stage4_4Past => "World 4 Boss (Past)",
+            GameStage::Stage5_4Past => "World 5 Boss (Past)",
+            _ => return None,
+        };
+        Some(BossFight {
+            boss,
+            phases: WORLD_BOSS_PHASES,
+        })
+    }
+
+    // the last two bosses split when they're beaten, their level exit is never a split
+    pub fn final_boss(self) -> Option<&'static str> {
+        match self {
+            GameStage::Stage6_4 => Some(SPOOKY.boss),
+            GameStage::Stage6_5 => Some(TOC_MAN.boss),
+            _ => None,
+        }
+    }
+
+    pub fn boss_phase_name(self, boss_state: u32) -> &'static str {
+        self.boss_fight()
+            .and_then(|fight| fight.phases.get(boss_state as usize).copied())
+            .unwrap_or("Unknown Phase")
+    }
+}
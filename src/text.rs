@@ -0,0 +1,41 @@
+use core::fmt::{self, Write};
+
+// a string written into a buffer on the stack, for the settings map keys and the string variables.
+// there are no allocations, a write that doesn't fit fails and keeps what was written before it
+pub struct Text<const N: usize> {
+    bytes: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> Default for Text<N> {
+    fn default() -> Self {
+        Self {
+            bytes: [0; N],
+            len: 0,
+        }
+    }
+}
+
+impl<const N: usize> Text<N> {
+    pub fn format(args: fmt::Arguments) -> Self {
+        let mut text = Self::default();
+        let _ = text.write_fmt(args);
+        text
+    }
+
+    pub fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap_or_default()
+    }
+}
+
+impl<const N: usize> Write for Text<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        if end > N {
+            return Err(fmt::Error);
+        }
+        self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+// an estimate of Time.timeScale, not the engine value. Time.timeScale, Application.targetFrameRate
+// and the vsync count live in UnityPlayer's native memory, they could be found with a signature
+// scan but no signatures for this build are known yet, so none of them are read.
+// the stage clock runs on the scaled game time, it's compared against the real time between ticks
+// from the tick clock. it's only measured while the player has control, the stage clock also
+// stops for cut-ins, the goal and some gimmicks while the stage stays in the playing state.
+// the load removal is made not to depend on the frame rate.
+
+// long enough that a frame of the stage clock is well under the legal range
+const WINDOW: f64 = 5.;
+const LEGAL_RANGE: (f64, f64) = (0.97, 1.03);
+
+#[derive(Default)]
+pub struct TimeScaleMeter {
+    real: f64,
+    game: f64,
+    last_stage_time: Option<f64>,
+}
+
+impl TimeScaleMeter {
+    // `stage_time` is only given while the stage is being played and the player has control.
+    // `real_time` is the measured time since the previous tick.
+    // gives the time scale every time a measurement finishes
+    pub fn update(&mut self, stage_time: Option<f64>, real_time: f64) -> Option<f64> {
+        let Some(time) = stage_time else {
+            self.last_stage_time = None;
+            return None;
+        };
+
+        // the clock going back is a restart, that tick isn't measured
+        if let Some(last) = self.last_stage_time.filter(|last| time >= *last) {
+            self.real += real_time;
+            self.game += time - last;
+        }
+        self.last_stage_time = Some(time);
+
+        if self.real < WINDOW {
+            return None;
+        }
+        let scale = self.game / self.real;
+        self.real = 0.;
+        self.game = 0.;
+        Some(scale)
+    }
+}
+
+pub fn is_legal_time_scale(scale: f64) -> bool {
+    (LEGAL_RANGE.0..=LEGAL_RANGE.1).contains(&scale)
+}
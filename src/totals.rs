@@ -0,0 +1,31 @@
+use core::ops::{Index, IndexMut};
+
+// a running total per slot, for the per level stats. `[f64; N]` only has a Default up to 32 and
+// the level tables are longer, so the structs keeping one can still derive theirs
+pub struct Totals<const N: usize>([f64; N]);
+
+impl<const N: usize> Default for Totals<N> {
+    fn default() -> Self {
+        Self([0.; N])
+    }
+}
+
+impl<const N: usize> Totals<N> {
+    pub fn iter(&self) -> impl Iterator<Item = &f64> {
+        self.0.iter()
+    }
+}
+
+impl<const N: usize> Index<usize> for Totals<N> {
+    type Output = f64;
+
+    fn index(&self, slot: usize) -> &f64 {
+        &self.0[slot]
+    }
+}
+
+impl<const N: usize> IndexMut<usize> for Totals<N> {
+    fn index_mut(&mut self, slot: usize) -> &mut f64 {
+        &mut self.0[slot]
+    }
+}
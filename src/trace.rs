@@ -0,0 +1,60 @@
+use asr::timer::TimerState;
+
+// everything the timeline needs from one tick, the names are already converted so the trace tool
+// doesn't have to know the game's enums
+#[derive(Clone, Copy, PartialEq)]
+pub struct TraceFrame {
+    pub level: &'static str,
+    pub player_state: &'static str,
+    pub stage_state: &'static str,
+    pub time_trial_state: &'static str,
+    pub checkpoint: i32,
+    pub loading: bool,
+    pub timer_state: TimerState,
+    pub split_index: Option<u64>,
+}
+
+// prints a `TRACE|...` line whenever the frame changes, read back by the `trace_timeline` host tool
+// the line format has to stay in sync with it
+#[derive(Default)]
+pub struct TraceRecorder {
+    tick: u64,
+    elapsed: f64,
+    last_frame: Option<TraceFrame>,
+}
+
+impl TraceRecorder {
+    pub fn record(&mut self, frame: TraceFrame, tick_time: f64) {
+        self.tick += 1;
+        self.elapsed += tick_time;
+
+        if self.last_frame == Some(frame) {
+            return;
+        }
+        self.last_frame = Some(frame);
+
+        asr::print_limited::<256>(&format_args!(
+            "TRACE|{}|{:.3}|{}|{}|{}|{}|{}|{}|{}|{}",
+            self.tick,
+            self.elapsed,
+            frame.level,
+            frame.player_state,
+            frame.stage_state,
+            frame.time_trial_state,
+            frame.checkpoint,
+            frame.loading as u8,
+            timer_state_to_string(frame.timer_state),
+            frame.split_index.map_or(-1, |index| index as i64),
+        ));
+    }
+}
+
+fn timer_state_to_string(state: TimerState) -> &'static str {
+    match state {
+        TimerState::NotRunning => "Not Running",
+        TimerState::Running => "Running",
+        TimerState::Paused => "Paused",
+        TimerState::Ended => "Ended",
+        _ => "Unknown",
+    }
+}